@@ -0,0 +1,75 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use pdb_handler::{chains_in_contact, chains_in_contact_with, ContactOptions};
+use pdbtbx::{Model, ReadOptions, PDB};
+
+/// The spacing between copies of crambin, close enough for neighbouring copies to be in contact.
+const SPACING: f64 = 25.0;
+
+/// Builds a synthetic assembly of `n * n * n` copies of crambin (1CRN) on a grid, each copy in
+/// its own chain.
+fn assembly(n: usize) -> PDB {
+    let (crambin, _) = ReadOptions::default()
+        .set_format(pdbtbx::Format::Pdb)
+        .set_level(pdbtbx::StrictnessLevel::Loose)
+        .read("example-pdbs/1crn.pdb")
+        .unwrap();
+    let template = crambin.chains().next().unwrap().clone();
+
+    let mut model = Model::new(0);
+    for i in 0..n * n * n {
+        let offset = [i % n, (i / n) % n, i / (n * n)].map(|k| k as f64 * SPACING);
+        let mut chain = template.clone();
+        chain.set_id(format!("C{}", i));
+        for atom in chain.atoms_mut() {
+            let (x, y, z) = atom.pos();
+            atom.set_pos((x + offset[0], y + offset[1], z + offset[2]))
+                .unwrap();
+        }
+        model.add_chain(chain);
+    }
+
+    let mut pdb = PDB::new();
+    pdb.add_model(model);
+    pdb
+}
+
+fn bench_chains_in_contact(c: &mut Criterion) {
+    let mut group = c.benchmark_group("chains_in_contact");
+    group.sample_size(10);
+
+    for n in [2, 4, 6, 8] {
+        let pdb = assembly(n);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(pdb.atom_count()),
+            &pdb,
+            |b, pdb| b.iter(|| chains_in_contact(black_box(pdb))),
+        );
+    }
+    group.finish();
+}
+
+fn bench_chains_in_contact_with(c: &mut Criterion) {
+    let mut group = c.benchmark_group("chains_in_contact_with");
+    group.sample_size(10);
+
+    let pdb = assembly(6);
+    let mut heavy = ContactOptions::default();
+    heavy.set_cutoff(3.9).set_heavy_atoms_only(true);
+    let mut ca = ContactOptions::default();
+    ca.set_cutoff(10.0).set_atom_names(&["CA"]);
+
+    group.bench_function("heavy_atoms_3.9", |b| {
+        b.iter(|| chains_in_contact_with(black_box(&pdb), &heavy))
+    });
+    group.bench_function("ca_10.0", |b| {
+        b.iter(|| chains_in_contact_with(black_box(&pdb), &ca))
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_chains_in_contact,
+    bench_chains_in_contact_with
+);
+criterion_main!(benches);
@@ -0,0 +1,472 @@
+use crate::constants::{AMINOACIDS, BLOSUM62, BLOSUM62_CODES, ONE_LETTER_CODES};
+use crate::renumber::renumber_by;
+use crate::residue_id::chain_residues;
+use crate::{classify_residue_name, modified_parent, MolecularType, ResidueId, ResidueMapping};
+use std::collections::{HashMap, HashSet};
+
+/// Aligned positions as indices into the first and second sequence, with `None` for gaps.
+type AlignedColumns = Vec<(Option<usize>, Option<usize>)>;
+
+/// The score of opening a gap in the alignment, i.e. of its first position.
+const GAP_OPEN: i32 = -10;
+/// The score of each further position of a gap.
+const GAP_EXTEND: i32 = -1;
+
+/// A global alignment of the protein sequences of two chains, as returned by [`align_chains`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainAlignment {
+    /// The alignment score, with BLOSUM62 and affine gap penalties.
+    pub score: i32,
+    /// The aligned reference sequence, in one-letter codes with `-` for gaps.
+    pub reference: String,
+    /// The aligned target sequence, in one-letter codes with `-` for gaps.
+    pub target: String,
+    /// The aligned residues as `(reference, target)`, with `None` for gaps.
+    pub pairs: Vec<(Option<ResidueId>, Option<ResidueId>)>,
+}
+
+/// Aligns the protein sequences of two chains with the Needleman–Wunsch algorithm.
+///
+/// The sequences are read from the amino acid residues of each chain, in file order, with modified
+/// residues read as their standard parent, e.g. `MSE` as `M`. The alignment is global and uses the
+/// BLOSUM62 substitution matrix, with a score of -10 to open a gap and -1 to extend it.
+///
+/// # Arguments
+///
+/// * `reference` - A reference to the `pdbtbx::PDB` structure of the reference chain.
+/// * `reference_chain` - The ID of the reference chain.
+/// * `target` - A reference to the `pdbtbx::PDB` structure of the target chain.
+/// * `target_chain` - The ID of the target chain.
+///
+/// # Returns
+///
+/// The alignment, or `None` if either chain is not found.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::align_chains;
+///
+/// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+/// let alignment = align_chains(&pdb, "A", &pdb, "A").unwrap();
+///
+/// assert_eq!(alignment.reference, alignment.target);
+/// assert!(alignment.pairs.iter().all(|(r, t)| r == t));
+/// ```
+///
+/// # Notes
+///
+/// Only amino acids are aligned; nucleotides, waters, ions and ligands are skipped.
+pub fn align_chains(
+    reference: &pdbtbx::PDB,
+    reference_chain: &str,
+    target: &pdbtbx::PDB,
+    target_chain: &str,
+) -> Option<ChainAlignment> {
+    let reference_sequence = chain_sequence(reference, reference_chain)?;
+    let target_sequence = chain_sequence(target, target_chain)?;
+
+    let a: Vec<char> = reference_sequence.iter().map(|(_, code)| *code).collect();
+    let b: Vec<char> = target_sequence.iter().map(|(_, code)| *code).collect();
+    let (score, columns) = needleman_wunsch(&a, &b, |x, y| blosum62(*x, *y));
+
+    let code = |column: Option<usize>, sequence: &[char]| column.map_or('-', |i| sequence[i]);
+    Some(ChainAlignment {
+        score,
+        reference: columns.iter().map(|(i, _)| code(*i, &a)).collect(),
+        target: columns.iter().map(|(_, j)| code(*j, &b)).collect(),
+        pairs: columns
+            .iter()
+            .map(|(i, j)| {
+                (
+                    i.map(|i| reference_sequence[i].0),
+                    j.map(|j| target_sequence[j].0),
+                )
+            })
+            .collect(),
+    })
+}
+
+/// Renumbers a chain of the target structure to match a chain of the reference structure.
+///
+/// The chains are aligned with [`align_chains`], and each target residue aligned to a reference
+/// residue takes its ID. Target residues that are not aligned to any reference residue are
+/// numbered after the last aligned reference residue before them: with insertion codes inside the
+/// chain, e.g. `52A` and `52B` after `52`, and with the following numbers at the C-terminus. Target
+/// residues before the first reference residue are numbered backwards from it.
+///
+/// # Arguments
+///
+/// * `target` - A mutable reference to the `pdbtbx::PDB` structure to renumber.
+/// * `target_chain` - The ID of the chain to renumber.
+/// * `reference` - A reference to the `pdbtbx::PDB` structure with the reference numbering.
+/// * `reference_chain` - The ID of the reference chain.
+///
+/// # Returns
+///
+/// The mapping from the old target residue IDs to the new ones, which are those of the
+/// corresponding reference residues, or `None` if either chain is not found or a residue that
+/// must be moved would get a number that overflows an `isize`.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::{renumber_offset, renumber_to_reference, ResidueId};
+///
+/// let (reference, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+/// let mut target = reference.clone();
+/// renumber_offset(&mut target, 100);
+///
+/// let mapping = renumber_to_reference(&mut target, "A", &reference, "A").unwrap();
+/// assert_eq!(mapping.get("A", ResidueId::new(101, None)), Some(ResidueId::new(1, None)));
+/// ```
+///
+/// # Notes
+///
+/// Only the amino acids of the target chain are renumbered; other residues of the chain, such as
+/// waters and ligands, keep their numbers. At most 26 consecutive residues can be inserted after
+/// the same reference residue, as insertion codes run from `A` to `Z`; further residues, and
+/// residues whose new number would overflow an `isize`, are left unchanged. A residue that keeps its number but clashes with the new number of another residue,
+/// e.g. a ligand numbered right after the C-terminus, is moved after the last residue of the chain
+/// and is included in the mapping.
+pub fn renumber_to_reference(
+    target: &mut pdbtbx::PDB,
+    target_chain: &str,
+    reference: &pdbtbx::PDB,
+    reference_chain: &str,
+) -> Option<ResidueMapping> {
+    let alignment = align_chains(reference, reference_chain, target, target_chain)?;
+    let pairs = &alignment.pairs;
+
+    let first_reference = pairs.iter().find_map(|(r, _)| *r);
+    let last_reference_column = pairs.iter().rposition(|(r, _)| r.is_some());
+    let leading = pairs.iter().take_while(|(r, _)| r.is_none()).count();
+
+    let mut new_ids: HashMap<ResidueId, ResidueId> = HashMap::new();
+    let mut last_reference: Option<ResidueId> = None;
+    let mut inserted = 0;
+
+    for (column, (r, t)) in pairs.iter().enumerate() {
+        if let Some(r) = r {
+            last_reference = Some(*r);
+            inserted = 0;
+        }
+        let Some(t) = t else {
+            continue;
+        };
+
+        let new = match (r, last_reference) {
+            (Some(r), _) => Some(*r),
+            (None, None) => first_reference
+                .and_then(|first| first.serial.checked_sub((leading - column) as isize))
+                .map(|serial| ResidueId::new(serial, None)),
+            (None, Some(last)) => {
+                inserted += 1;
+                if last_reference_column.is_some_and(|c| column > c) {
+                    last.serial
+                        .checked_add(inserted)
+                        .map(|serial| ResidueId::new(serial, None))
+                } else {
+                    insertion_code(last.icode, inserted)
+                        .map(|icode| ResidueId::new(last.serial, Some(icode)))
+                }
+            }
+        };
+        if let Some(new) = new {
+            new_ids.insert(*t, new);
+        }
+    }
+
+    // Residues that are not renumbered keep their IDs, unless a renumbered residue takes it; those
+    // are moved after the last residue of the chain
+    let (_, residues) = chain_residues(target)
+        .into_iter()
+        .find(|(chain, _)| *chain == target_chain)?;
+    let kept: Vec<ResidueId> = residues
+        .into_iter()
+        .map(ResidueId::from_residue)
+        .filter(|id| !new_ids.contains_key(id))
+        .collect();
+    let taken: HashSet<ResidueId> = new_ids.values().copied().collect();
+    let mut last_serial = taken.iter().chain(&kept).map(|id| id.serial).max()?;
+    for id in kept {
+        if taken.contains(&id) {
+            last_serial = last_serial.checked_add(1)?;
+            new_ids.insert(id, ResidueId::new(last_serial, None));
+        }
+    }
+
+    Some(renumber_by(target, |chain, old| {
+        if chain == target_chain {
+            new_ids.get(&old).copied()
+        } else {
+            None
+        }
+    }))
+}
+
+/// Returns the amino acids of the given chain with their one-letter codes, or `None` if the chain
+/// is not found.
+fn chain_sequence(structure: &pdbtbx::PDB, chain_id: &str) -> Option<Vec<(ResidueId, char)>> {
+    let (_, residues) = chain_residues(structure)
+        .into_iter()
+        .find(|(chain, _)| *chain == chain_id)?;
+
+    Some(
+        residues
+            .into_iter()
+            .filter_map(|residue| {
+                let name = residue.name()?;
+                if classify_residue_name(name) != Some(MolecularType::Protein) {
+                    return None;
+                }
+                let code = one_letter_code(name).unwrap_or('X');
+                Some((ResidueId::from_residue(residue), code))
+            })
+            .collect(),
+    )
+}
+
+/// Returns the `n`-th insertion code after `icode`, e.g. `B` for the second one after none, or
+/// `None` if it is past `Z`.
+fn insertion_code(icode: Option<char>, n: isize) -> Option<char> {
+    let start = icode.map_or('A' as u32 - 1, |c| c as u32);
+    char::from_u32(start + n as u32).filter(|c| c.is_ascii_uppercase())
+}
+
+/// Returns the one-letter code of an amino acid, or of the parent of a modified amino acid, or
+/// `None` if the residue is not an amino acid.
+fn one_letter_code(res_name: &str) -> Option<char> {
+    let parent = modified_parent(res_name).unwrap_or(res_name);
+    AMINOACIDS
+        .iter()
+        .position(|aa| *aa == parent)
+        .map(|i| ONE_LETTER_CODES[i])
+}
+
+/// Returns the alignment score of two residue names: the BLOSUM62 score for amino acids, and 5
+/// for identical and -4 for different names otherwise, e.g. for nucleotides.
+pub(crate) fn residue_name_score(a: &str, b: &str) -> i32 {
+    match (one_letter_code(a), one_letter_code(b)) {
+        (Some(a), Some(b)) => blosum62(a, b),
+        _ if a == b => 5,
+        _ => -4,
+    }
+}
+
+/// Returns the BLOSUM62 score of two one-letter codes. Unknown codes are scored as `X`.
+fn blosum62(a: char, b: char) -> i32 {
+    let index = |c: char| {
+        BLOSUM62_CODES
+            .find(c)
+            .unwrap_or_else(|| BLOSUM62_CODES.find('X').unwrap())
+    };
+    BLOSUM62[index(a)][index(b)]
+}
+
+/// Globally aligns two sequences with affine gap penalties (Gotoh's variant of Needleman–Wunsch),
+/// scoring aligned items with `score`. Returns the alignment score and the aligned columns as
+/// indices into `a` and `b`, with `None` for gaps.
+pub(crate) fn needleman_wunsch<T, F>(a: &[T], b: &[T], score: F) -> (i32, AlignedColumns)
+where
+    F: Fn(&T, &T) -> i32,
+{
+    const NONE: i32 = i32::MIN / 4;
+    let (n, m) = (a.len(), b.len());
+
+    // Best scores of the alignments of a[..i] and b[..j] ending with a match, with a gap in b
+    // (a[i - 1] aligned to nothing) and with a gap in a
+    let mut matched = vec![vec![NONE; m + 1]; n + 1];
+    let mut gap_b = vec![vec![NONE; m + 1]; n + 1];
+    let mut gap_a = vec![vec![NONE; m + 1]; n + 1];
+    matched[0][0] = 0;
+    for (i, row) in gap_b.iter_mut().enumerate().skip(1) {
+        row[0] = GAP_OPEN + (i as i32 - 1) * GAP_EXTEND;
+    }
+    for (j, score) in gap_a[0].iter_mut().enumerate().skip(1) {
+        *score = GAP_OPEN + (j as i32 - 1) * GAP_EXTEND;
+    }
+
+    for i in 1..=n {
+        for j in 1..=m {
+            matched[i][j] = score(&a[i - 1], &b[j - 1])
+                + matched[i - 1][j - 1]
+                    .max(gap_b[i - 1][j - 1])
+                    .max(gap_a[i - 1][j - 1]);
+            gap_b[i][j] = (matched[i - 1][j] + GAP_OPEN)
+                .max(gap_b[i - 1][j] + GAP_EXTEND)
+                .max(gap_a[i - 1][j] + GAP_OPEN);
+            gap_a[i][j] = (matched[i][j - 1] + GAP_OPEN)
+                .max(gap_a[i][j - 1] + GAP_EXTEND)
+                .max(gap_b[i][j - 1] + GAP_OPEN);
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Matched,
+        GapB,
+        GapA,
+    }
+    let best = |i: usize, j: usize| {
+        [
+            (State::Matched, matched[i][j]),
+            (State::GapB, gap_b[i][j]),
+            (State::GapA, gap_a[i][j]),
+        ]
+        .into_iter()
+        .fold(
+            (State::Matched, NONE),
+            |best, s| if s.1 > best.1 { s } else { best },
+        )
+    };
+
+    let (mut state, total) = best(n, m);
+    let (mut i, mut j) = (n, m);
+    let mut columns = Vec::with_capacity(n + m);
+
+    while i > 0 || j > 0 {
+        match state {
+            State::Matched => {
+                columns.push((Some(i - 1), Some(j - 1)));
+                i -= 1;
+                j -= 1;
+                state = best(i, j).0;
+            }
+            State::GapB => {
+                columns.push((Some(i - 1), None));
+                let current = gap_b[i][j];
+                i -= 1;
+                state = if current == gap_b[i][j] + GAP_EXTEND {
+                    State::GapB
+                } else if current == matched[i][j] + GAP_OPEN {
+                    State::Matched
+                } else {
+                    State::GapA
+                };
+            }
+            State::GapA => {
+                columns.push((None, Some(j - 1)));
+                let current = gap_a[i][j];
+                j -= 1;
+                state = if current == gap_a[i][j] + GAP_EXTEND {
+                    State::GapA
+                } else if current == matched[i][j] + GAP_OPEN {
+                    State::Matched
+                } else {
+                    State::GapB
+                };
+            }
+        }
+    }
+    columns.reverse();
+
+    (total, columns)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{identify_residue_ids, renumber_offset, ResidueOrder};
+    use pdbtbx::ReadOptions;
+
+    #[test]
+    fn test_blosum62() {
+        for (i, row) in BLOSUM62.iter().enumerate() {
+            for (j, score) in row.iter().enumerate() {
+                assert_eq!(*score, BLOSUM62[j][i]);
+            }
+        }
+        assert_eq!(blosum62('W', 'W'), 11);
+        assert_eq!(blosum62('A', 'R'), -1);
+        assert_eq!(blosum62('U', 'A'), 0);
+    }
+
+    #[test]
+    fn test_needleman_wunsch() {
+        let a: Vec<char> = "HEAGAWGHEE".chars().collect();
+        let b: Vec<char> = "HEAGAWGHEE".chars().collect();
+        let (score, columns) = needleman_wunsch(&a, &b, |x, y| blosum62(*x, *y));
+        assert_eq!(score, a.iter().map(|c| blosum62(*c, *c)).sum::<i32>());
+        assert!(columns.iter().all(|(i, j)| i == j));
+
+        let b: Vec<char> = "HEAWGHEE".chars().collect();
+        let (_, columns) = needleman_wunsch(&a, &b, |x, y| blosum62(*x, *y));
+        let gaps: Vec<usize> = columns
+            .iter()
+            .filter(|(_, j)| j.is_none())
+            .filter_map(|(i, _)| *i)
+            .collect();
+        assert_eq!(gaps.len(), 2);
+        assert_eq!(gaps[1], gaps[0] + 1);
+
+        let (score, columns) = needleman_wunsch(&a, &[], |x, y| blosum62(*x, *y));
+        assert_eq!(score, GAP_OPEN + 9 * GAP_EXTEND);
+        assert_eq!(columns.len(), 10);
+    }
+
+    #[test]
+    fn test_renumber_to_reference() {
+        let (crambin, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("example-pdbs/1crn.pdb")
+            .unwrap();
+
+        // The reference misses residues 20 and 46, the target residue 140
+        let mut reference = crambin.clone();
+        reference.remove_residues_by(|residue| matches!(residue.serial_number(), 20 | 46));
+        let mut target = crambin.clone();
+        renumber_offset(&mut target, 100);
+        target.remove_residues_by(|residue| residue.serial_number() == 140);
+
+        let alignment = align_chains(&reference, "A", &target, "A").unwrap();
+        assert_eq!(alignment.reference.len(), 46);
+        assert!(alignment.reference.starts_with("TTCCPSIVARSNFNVCRLP-TPEA"));
+        assert!(alignment.reference.ends_with("PGATCPGDYA-"));
+        assert!(alignment.target.ends_with("PGAT-PGDYAN"));
+
+        let mapping = renumber_to_reference(&mut target, "A", &reference, "A").unwrap();
+        assert_eq!(mapping.len(), 45);
+        let new_id = |serial: isize| mapping.get("A", ResidueId::new(serial, None));
+        assert_eq!(new_id(101), Some(ResidueId::new(1, None)));
+        assert_eq!(new_id(119), Some(ResidueId::new(19, None)));
+        assert_eq!(new_id(120), Some(ResidueId::new(19, Some('A'))));
+        assert_eq!(new_id(121), Some(ResidueId::new(21, None)));
+        assert_eq!(new_id(141), Some(ResidueId::new(41, None)));
+        assert_eq!(new_id(146), Some(ResidueId::new(46, None)));
+
+        let ids = identify_residue_ids(&target, ResidueOrder::File);
+        assert_eq!(ids["A"][19], ResidueId::new(19, Some('A')));
+        assert!(!ids["A"].contains(&ResidueId::new(40, None)));
+    }
+
+    #[test]
+    fn test_renumber_to_reference_ligand() {
+        // The ligand of the target is numbered right after the C-terminus of the reference
+        let (reference, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/renumber_reference.pdb")
+            .unwrap();
+        let mut target = reference.clone();
+
+        let mapping = renumber_to_reference(&mut target, "B", &reference, "A").unwrap();
+
+        assert_eq!(mapping.len(), 4);
+        assert_eq!(
+            mapping.get("B", ResidueId::new(3, None)),
+            Some(ResidueId::new(202, None))
+        );
+        assert_eq!(
+            mapping.get("B", ResidueId::new(202, None)),
+            Some(ResidueId::new(203, None))
+        );
+        let ids = identify_residue_ids(&target, ResidueOrder::File);
+        assert_eq!(
+            ids["B"],
+            (200..=203)
+                .map(|serial| ResidueId::new(serial, None))
+                .collect::<Vec<_>>()
+        );
+    }
+}
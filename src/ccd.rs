@@ -0,0 +1,329 @@
+use crate::{MolecularType, PdbHandlerError, PdbLineFilter, ResidueLookup};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+/// A chemical component as described in the wwPDB Chemical Component Dictionary (CCD).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    /// The component identifier, e.g. `ALA` or `HOH`.
+    pub id: String,
+    /// The full name of the component.
+    pub name: Option<String>,
+    /// The component type, e.g. `L-PEPTIDE LINKING` or `NON-POLYMER`.
+    pub component_type: String,
+    /// The standard parent component of a modified residue, e.g. `MET` for `MSE`.
+    pub parent: Option<String>,
+    /// The chemical formula of the component, e.g. `C3 H7 N O2`.
+    pub formula: Option<String>,
+}
+
+impl Component {
+    /// Returns the `MolecularType` of this component based on its CCD type and formula.
+    ///
+    /// Polymer linking types are mapped to `Protein`, `Dna` and `Rna`, saccharides to
+    /// `Carbohydrate`. Non-polymer components are classified as `Water` (`HOH`, `DOD`),
+    /// `Ion` (a single atom in the formula, e.g. `Zn`) or `Ligand`.
+    pub fn molecular_type(&self) -> MolecularType {
+        let component_type = self.component_type.to_uppercase();
+        if component_type.contains("PEPTIDE") && !component_type.contains("PEPTIDE-LIKE") {
+            MolecularType::Protein
+        } else if component_type.contains("DNA") {
+            MolecularType::Dna
+        } else if component_type.contains("RNA") {
+            MolecularType::Rna
+        } else if component_type.contains("SACCHARIDE") {
+            MolecularType::Carbohydrate
+        } else if self.id == "HOH" || self.id == "DOD" {
+            MolecularType::Water
+        } else if self.is_single_atom() {
+            MolecularType::Ion
+        } else {
+            MolecularType::Ligand
+        }
+    }
+
+    /// Returns true if the formula of this component consists of a single atom, e.g. `Zn` or `Cl`.
+    fn is_single_atom(&self) -> bool {
+        match &self.formula {
+            Some(formula) => {
+                let formula = formula.trim();
+                !formula.is_empty()
+                    && !formula.contains(' ')
+                    && formula.chars().all(|c| c.is_ascii_alphabetic())
+            }
+            None => false,
+        }
+    }
+}
+
+/// A registry of chemical components, loaded from a wwPDB Chemical Component Dictionary file.
+///
+/// The registry can be passed to the `*_with` functions (e.g. [`crate::identify_molecular_types_with`])
+/// to classify residues using the dictionary instead of the built-in residue tables.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComponentRegistry {
+    components: HashMap<String, Component>,
+}
+
+impl ComponentRegistry {
+    /// Loads a component registry from a local `components.cif` file, or a subset of it.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - A string slice that holds the path to the CCD file.
+    ///
+    /// # Returns
+    ///
+    /// A `ComponentRegistry` with one entry per `data_` block found in the file, or a
+    /// `PdbHandlerError` if the file cannot be read or is malformed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pdb_handler::ComponentRegistry;
+    ///
+    /// let registry = ComponentRegistry::from_file("test_data/components.cif").unwrap();
+    /// let ala = registry.get("ALA").unwrap();
+    /// println!("{}: {}", ala.id, ala.component_type);
+    /// ```
+    pub fn from_file(path: &str) -> Result<Self, PdbHandlerError> {
+        let file = File::open(path)?;
+        Self::from_reader(BufReader::new(file))
+    }
+
+    /// Loads a component registry from any buffered reader over CCD content.
+    ///
+    /// Only the `_chem_comp` category is read; all other categories and loops are skipped.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self, PdbHandlerError> {
+        let mut registry = ComponentRegistry::default();
+        let mut items: HashMap<String, String> = HashMap::new();
+        let mut lines = PdbLineFilter::new(reader);
+
+        while let Some(line) = lines.next() {
+            let line = line?;
+
+            if line.starts_with("data_") {
+                registry.insert_items(&mut items)?;
+                continue;
+            }
+
+            let Some(item) = line.strip_prefix("_chem_comp.") else {
+                continue;
+            };
+
+            let (key, value) = match item.split_once(char::is_whitespace) {
+                Some((key, value)) => (key.to_string(), value.trim().to_string()),
+                None => (item.trim().to_string(), String::new()),
+            };
+
+            // Values that do not fit on the key line are given on the next line(s)
+            let value = if value.is_empty() {
+                match lines.next() {
+                    Some(next) => {
+                        let next = next?;
+                        match next.strip_prefix(';') {
+                            Some(text) => Some(read_text_field(text, &mut lines)?),
+                            None => parse_value(&next),
+                        }
+                    }
+                    None => return Err(parse_error(format!("missing value for {}", key))),
+                }
+            } else {
+                parse_value(&value)
+            };
+
+            if let Some(value) = value {
+                items.insert(key, value);
+            }
+        }
+        registry.insert_items(&mut items)?;
+
+        Ok(registry)
+    }
+
+    /// Returns the component with the given identifier, if present.
+    pub fn get(&self, id: &str) -> Option<&Component> {
+        self.components.get(&id.to_uppercase())
+    }
+
+    /// Adds a component to the registry, replacing any component with the same identifier.
+    pub fn insert(&mut self, component: Component) {
+        self.components
+            .insert(component.id.to_uppercase(), component);
+    }
+
+    /// Returns the number of components in the registry.
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Returns true if the registry contains no components.
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Returns an iterator over all components in the registry, in arbitrary order.
+    pub fn components(&self) -> impl Iterator<Item = &Component> + '_ {
+        self.components.values()
+    }
+
+    /// Builds a `Component` from the collected `_chem_comp` items and adds it to the registry.
+    fn insert_items(&mut self, items: &mut HashMap<String, String>) -> Result<(), PdbHandlerError> {
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        let mut take = |key: &str| items.remove(key);
+        let id = take("id").ok_or_else(|| parse_error("component without _chem_comp.id"))?;
+        let component = Component {
+            name: take("name"),
+            component_type: take("type").unwrap_or_default(),
+            parent: take("mon_nstd_parent_comp_id"),
+            formula: take("formula"),
+            id,
+        };
+        items.clear();
+
+        self.insert(component);
+        Ok(())
+    }
+}
+
+impl ResidueLookup for ComponentRegistry {
+    fn molecular_type(&self, res_name: &str) -> Option<MolecularType> {
+        let component = self.get(res_name)?;
+        let mol_type = component.molecular_type();
+
+        // Some modified residues are non-polymer components with a polymer parent
+        if mol_type == MolecularType::Ligand {
+            if let Some(parent) = component.parent.as_ref().and_then(|p| self.get(p)) {
+                return Some(parent.molecular_type());
+            }
+        }
+
+        Some(mol_type)
+    }
+}
+
+/// Reads a CIF semicolon-delimited text field, starting with the content after the opening `;`.
+fn read_text_field<I>(first: &str, lines: &mut I) -> Result<String, PdbHandlerError>
+where
+    I: Iterator<Item = Result<String, PdbHandlerError>>,
+{
+    let mut text = first.trim().to_string();
+    for line in lines.by_ref() {
+        let line = line?;
+        if line.starts_with(';') {
+            return Ok(text);
+        }
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(line.trim());
+    }
+    Err(parse_error("unterminated text field"))
+}
+
+/// Parses a single CIF value, removing quotes. The CIF null values `?` and `.` return `None`.
+fn parse_value(value: &str) -> Option<String> {
+    let value = value.trim();
+    let unquoted = match value.chars().next() {
+        Some(quote @ ('\'' | '"')) => value[1..]
+            .rfind(quote)
+            .map(|end| &value[1..end + 1])
+            .unwrap_or(&value[1..]),
+        _ => value.split_whitespace().next().unwrap_or(""),
+    };
+
+    match unquoted {
+        "" | "?" | "." => None,
+        _ => Some(unquoted.to_string()),
+    }
+}
+
+fn parse_error(message: impl Into<String>) -> PdbHandlerError {
+    PdbHandlerError::Parse(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{identify_molecular_types_with, identify_unknowns_with};
+    use pdbtbx::ReadOptions;
+
+    #[test]
+    fn test_registry_from_file() {
+        let registry = ComponentRegistry::from_file("test_data/components.cif").unwrap();
+
+        assert_eq!(registry.len(), 10);
+
+        let mse = registry.get("MSE").unwrap();
+        assert_eq!(mse.component_type, "L-PEPTIDE LINKING");
+        assert_eq!(mse.parent, Some("MET".to_string()));
+        assert_eq!(mse.formula, Some("C5 H11 N O2 Se".to_string()));
+
+        let cm5 = registry.get("5CM").unwrap();
+        assert_eq!(
+            cm5.name,
+            Some("5-METHYL-2'-DEOXY-CYTIDINE 5'-MONOPHOSPHATE".to_string())
+        );
+        assert_eq!(cm5.parent, Some("DC".to_string()));
+
+        assert_eq!(registry.get("ALA").unwrap().parent, None);
+    }
+
+    #[test]
+    fn test_component_molecular_type() {
+        let registry = ComponentRegistry::from_file("test_data/components.cif").unwrap();
+
+        let expected = [
+            ("ALA", MolecularType::Protein),
+            ("MSE", MolecularType::Protein),
+            ("DC", MolecularType::Dna),
+            ("U", MolecularType::Rna),
+            ("NAG", MolecularType::Carbohydrate),
+            ("ZN", MolecularType::Ion),
+            ("HOH", MolecularType::Water),
+            ("ATP", MolecularType::Ligand),
+        ];
+
+        for (id, mol_type) in expected {
+            assert_eq!(registry.molecular_type(id), Some(mol_type));
+        }
+        assert_eq!(registry.molecular_type("I09"), None);
+    }
+
+    #[test]
+    fn test_identify_with_registry() {
+        let registry = ComponentRegistry::from_file("test_data/components.cif").unwrap();
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/hetero_mix.pdb")
+            .unwrap();
+
+        let mol_types = identify_molecular_types_with(&structure, &registry);
+        let unknowns = identify_unknowns_with(&structure, &registry);
+
+        assert_eq!(
+            mol_types["A"],
+            vec![
+                MolecularType::Protein,
+                MolecularType::Carbohydrate,
+                MolecularType::Ligand,
+                MolecularType::Ion,
+                MolecularType::Water,
+            ]
+        );
+        assert_eq!(unknowns["A"], vec!["I09".to_string()]);
+    }
+
+    #[test]
+    fn test_registry_unterminated_text_field() {
+        let content = "data_XXX\n_chem_comp.id XXX\n_chem_comp.name\n;never closed\n";
+        let result = ComponentRegistry::from_reader(content.as_bytes());
+
+        assert!(matches!(result, Err(PdbHandlerError::Parse(_))));
+    }
+}
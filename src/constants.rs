@@ -4,3 +4,106 @@ pub const AMINOACIDS: [&str; 20] = [
 ];
 
 pub const DNA: [&str; 8] = ["ADE", "CYT", "GUA", "THY", "DA", "DC", "DG", "DT"];
+
+pub const RNA: [&str; 9] = ["A", "C", "G", "U", "URA", "RA", "RC", "RG", "RU"];
+
+pub const WATER: [&str; 7] = ["HOH", "WAT", "H2O", "DOD", "TIP", "TIP3", "SOL"];
+
+pub const IONS: [&str; 33] = [
+    "LI", "NA", "K", "RB", "CS", "MG", "CA", "SR", "BA", "MN", "MN3", "FE", "FE2", "CO", "3CO",
+    "NI", "3NI", "CU", "CU1", "ZN", "CD", "HG", "PB", "PT", "AU", "AG", "AL", "GA", "YB", "F",
+    "CL", "BR", "IOD",
+];
+
+pub const CARBOHYDRATES: [&str; 21] = [
+    "NAG", "NDG", "MAN", "BMA", "GAL", "GLA", "GLC", "BGC", "FUC", "FUL", "SIA", "XYS", "XYP",
+    "FRU", "A2G", "NGA", "GCU", "BDP", "IDS", "SGN", "RAM",
+];
+
+pub const MODIFIED_RESIDUES: [(&str, &str); 42] = [
+    ("MSE", "MET"),
+    ("FME", "MET"),
+    ("SEP", "SER"),
+    ("SAC", "SER"),
+    ("TPO", "THR"),
+    ("PTR", "TYR"),
+    ("HYP", "PRO"),
+    ("MLY", "LYS"),
+    ("M3L", "LYS"),
+    ("KCX", "LYS"),
+    ("ALY", "LYS"),
+    ("CSO", "CYS"),
+    ("CSD", "CYS"),
+    ("CME", "CYS"),
+    ("OCS", "CYS"),
+    ("CAS", "CYS"),
+    ("MLE", "LEU"),
+    ("NLE", "LEU"),
+    ("MVA", "VAL"),
+    ("PCA", "GLN"),
+    ("CGU", "GLU"),
+    ("HIC", "HIS"),
+    ("NEP", "HIS"),
+    ("AIB", "ALA"),
+    ("DAL", "ALA"),
+    ("5CM", "DC"),
+    ("CBR", "DC"),
+    ("8OG", "DG"),
+    ("6OG", "DG"),
+    ("6MA", "DA"),
+    ("5MC", "C"),
+    ("OMC", "C"),
+    ("PSU", "U"),
+    ("5MU", "U"),
+    ("H2U", "U"),
+    ("5BU", "U"),
+    ("2MG", "G"),
+    ("7MG", "G"),
+    ("M2G", "G"),
+    ("OMG", "G"),
+    ("1MA", "A"),
+    ("MIA", "A"),
+];
+
+pub const TWO_LETTER_ELEMENTS: [&str; 49] = [
+    "LI", "BE", "NA", "MG", "AL", "SI", "CL", "AR", "CA", "SC", "TI", "CR", "MN", "FE", "CO", "NI",
+    "CU", "ZN", "GA", "GE", "AS", "SE", "BR", "KR", "RB", "SR", "ZR", "MO", "RU", "RH", "PD", "AG",
+    "CD", "IN", "SN", "SB", "TE", "XE", "CS", "BA", "LA", "GD", "YB", "PT", "AU", "HG", "TL", "PB",
+    "BI",
+];
+
+pub const ONE_LETTER_CODES: [char; 20] = [
+    'A', 'R', 'N', 'D', 'C', 'Q', 'E', 'G', 'H', 'I', 'L', 'K', 'M', 'F', 'P', 'S', 'T', 'W', 'Y',
+    'V',
+];
+
+pub const BLOSUM62_CODES: &str = "ARNDCQEGHILKMFPSTWYVBZX*";
+
+// Rows and columns follow the order of BLOSUM62_CODES
+#[rustfmt::skip]
+pub const BLOSUM62: [[i32; 24]; 24] = [
+    [ 4, -1, -2, -2,  0, -1, -1,  0, -2, -1, -1, -1, -1, -2, -1,  1,  0, -3, -2,  0, -2, -1,  0, -4],
+    [-1,  5,  0, -2, -3,  1,  0, -2,  0, -3, -2,  2, -1, -3, -2, -1, -1, -3, -2, -3, -1,  0, -1, -4],
+    [-2,  0,  6,  1, -3,  0,  0,  0,  1, -3, -3,  0, -2, -3, -2,  1,  0, -4, -2, -3,  3,  0, -1, -4],
+    [-2, -2,  1,  6, -3,  0,  2, -1, -1, -3, -4, -1, -3, -3, -1,  0, -1, -4, -3, -3,  4,  1, -1, -4],
+    [ 0, -3, -3, -3,  9, -3, -4, -3, -3, -1, -1, -3, -1, -2, -3, -1, -1, -2, -2, -1, -3, -3, -2, -4],
+    [-1,  1,  0,  0, -3,  5,  2, -2,  0, -3, -2,  1,  0, -3, -1,  0, -1, -2, -1, -2,  0,  3, -1, -4],
+    [-1,  0,  0,  2, -4,  2,  5, -2,  0, -3, -3,  1, -2, -3, -1,  0, -1, -3, -2, -2,  1,  4, -1, -4],
+    [ 0, -2,  0, -1, -3, -2, -2,  6, -2, -4, -4, -2, -3, -3, -2,  0, -2, -2, -3, -3, -1, -2, -1, -4],
+    [-2,  0,  1, -1, -3,  0,  0, -2,  8, -3, -3, -1, -2, -1, -2, -1, -2, -2,  2, -3,  0,  0, -1, -4],
+    [-1, -3, -3, -3, -1, -3, -3, -4, -3,  4,  2, -3,  1,  0, -3, -2, -1, -3, -1,  3, -3, -3, -1, -4],
+    [-1, -2, -3, -4, -1, -2, -3, -4, -3,  2,  4, -2,  2,  0, -3, -2, -1, -2, -1,  1, -4, -3, -1, -4],
+    [-1,  2,  0, -1, -3,  1,  1, -2, -1, -3, -2,  5, -1, -3, -1,  0, -1, -3, -2, -2,  0,  1, -1, -4],
+    [-1, -1, -2, -3, -1,  0, -2, -3, -2,  1,  2, -1,  5,  0, -2, -1, -1, -1, -1,  1, -3, -1, -1, -4],
+    [-2, -3, -3, -3, -2, -3, -3, -3, -1,  0,  0, -3,  0,  6, -4, -2, -2,  1,  3, -1, -3, -3, -1, -4],
+    [-1, -2, -2, -1, -3, -1, -1, -2, -2, -3, -3, -1, -2, -4,  7, -1, -1, -4, -3, -2, -2, -1, -2, -4],
+    [ 1, -1,  1,  0, -1,  0,  0,  0, -1, -2, -2,  0, -1, -2, -1,  4,  1, -3, -2, -2,  0,  0,  0, -4],
+    [ 0, -1,  0, -1, -1, -1, -1, -2, -2, -1, -1, -1, -1, -2, -1,  1,  5, -2, -2,  0, -1, -1,  0, -4],
+    [-3, -3, -4, -4, -2, -2, -3, -2, -2, -3, -2, -3, -1,  1, -4, -3, -2, 11,  2, -3, -4, -3, -2, -4],
+    [-2, -2, -2, -3, -2, -1, -2, -3,  2, -1, -1, -2, -1,  3, -3, -2, -2,  2,  7, -1, -3, -2, -1, -4],
+    [ 0, -3, -3, -3, -1, -2, -2, -3, -3,  3,  1, -2,  1, -1, -2, -2,  0, -3, -1,  4, -3, -2, -1, -4],
+    [-2, -1,  3,  4, -3,  0,  1, -1,  0, -3, -4,  0, -3, -3, -2,  0, -1, -4, -3, -3,  4,  1, -1, -4],
+    [-1,  0,  0,  1, -3,  3,  4, -2,  0, -3, -3,  1, -1, -3, -1,  0, -1, -3, -2, -2,  1,  4, -1, -4],
+    [ 0, -1, -1, -1, -2, -1, -1, -1, -1, -1, -1, -1, -1, -1, -2,  0,  0, -2, -1, -1, -1, -1, -1, -4],
+    [-4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4,  1],
+];
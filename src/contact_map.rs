@@ -0,0 +1,466 @@
+use crate::contacts::{atom_contacts, chain_order, named_chain_residues};
+use crate::{ContactOptions, InterfaceResidue, PdbHandlerError, ResidueId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+
+/// A pair of residues from different chains in contact, as returned by [`identify_residue_contacts`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResidueContact {
+    /// The ID of the chain of the first residue, in order of appearance in the structure.
+    pub chain_a: String,
+    /// The first residue.
+    pub residue_a: InterfaceResidue,
+    /// The ID of the chain of the second residue.
+    pub chain_b: String,
+    /// The second residue.
+    pub residue_b: InterfaceResidue,
+    /// The minimum distance, in Å, between the atoms of both residues.
+    pub min_distance: f64,
+    /// The number of atom pairs within the cutoff.
+    pub atom_contacts: usize,
+}
+
+/// A single contact of a [`ContactMap`], with the indices of both residues.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ContactMapEntry {
+    /// The index of the residue in `residues_a`.
+    pub row: usize,
+    /// The index of the residue in `residues_b`.
+    pub column: usize,
+    /// The minimum distance, in Å, between the atoms of both residues.
+    pub min_distance: f64,
+    /// The number of atom pairs within the cutoff.
+    pub atom_contacts: usize,
+}
+
+/// The residue contact map between two chains, as returned by [`residue_contact_maps`].
+///
+/// The map holds all residues of both chains and the contacts between them. It can be written as
+/// a dense matrix of atom contact counts, with one row per residue of the first chain and one
+/// column per residue of the second chain, or as a sparse list of contacts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ContactMap {
+    /// The ID of the first chain, in order of appearance in the structure.
+    pub chain_a: String,
+    /// The ID of the second chain.
+    pub chain_b: String,
+    /// All residues of the first chain, in order of appearance.
+    pub residues_a: Vec<InterfaceResidue>,
+    /// All residues of the second chain, in order of appearance.
+    pub residues_b: Vec<InterfaceResidue>,
+    /// The residue pairs in contact, ordered by row and column.
+    pub contacts: Vec<ContactMapEntry>,
+}
+
+/// The file format of an exported [`ContactMap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContactMapFormat {
+    /// Comma-separated values with a header row.
+    Csv,
+    /// A JSON object.
+    Json,
+    /// A NumPy `.npy` array.
+    Npy,
+}
+
+impl ContactMap {
+    /// Returns the dense matrix of atom contact counts, with one row per residue of the first
+    /// chain and one column per residue of the second chain. Residues that are not in contact
+    /// have a count of 0.
+    pub fn dense(&self) -> Vec<Vec<usize>> {
+        let mut matrix = vec![vec![0; self.residues_b.len()]; self.residues_a.len()];
+        for entry in &self.contacts {
+            matrix[entry.row][entry.column] = entry.atom_contacts;
+        }
+        matrix
+    }
+
+    /// Writes the dense matrix of atom contact counts (see [`ContactMap::dense`]) to `writer`.
+    ///
+    /// * `Csv` - A header row with the residue labels of the second chain (e.g. `46` or `52A`),
+    ///   then one row per residue of the first chain, starting with its label.
+    /// * `Json` - An object with the chain IDs, the residues of both chains and the matrix as
+    ///   `atom_contacts`.
+    /// * `Npy` - A 2D array of 64-bit integers.
+    pub fn write_dense<W: Write>(
+        &self,
+        mut writer: W,
+        format: ContactMapFormat,
+    ) -> Result<(), PdbHandlerError> {
+        let matrix = self.dense();
+
+        match format {
+            ContactMapFormat::Csv => {
+                let header: Vec<String> = self.residues_b.iter().map(residue_label).collect();
+                writeln!(writer, "residue,{}", header.join(","))?;
+                for (residue, row) in self.residues_a.iter().zip(&matrix) {
+                    let values: Vec<String> = row.iter().map(|count| count.to_string()).collect();
+                    writeln!(writer, "{},{}", residue_label(residue), values.join(","))?;
+                }
+            }
+            ContactMapFormat::Json => {
+                let dense = serde_json::json!({
+                    "chain_a": self.chain_a,
+                    "chain_b": self.chain_b,
+                    "residues_a": self.residues_a,
+                    "residues_b": self.residues_b,
+                    "atom_contacts": matrix,
+                });
+                serde_json::to_writer(&mut writer, &dense)?;
+            }
+            ContactMapFormat::Npy => {
+                let shape = [self.residues_a.len(), self.residues_b.len()];
+                write_npy_header(&mut writer, "<i8", &shape)?;
+                for count in matrix.iter().flatten() {
+                    writer.write_all(&(*count as i64).to_le_bytes())?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the list of contacts to `writer`.
+    ///
+    /// * `Csv` - One row per contact, with the columns `chain_a`, `residue_a`, `name_a`, `chain_b`,
+    ///   `residue_b`, `name_b`, `min_distance` and `atom_contacts`.
+    /// * `Json` - The serialised `ContactMap`.
+    /// * `Npy` - A 2D array of 64-bit floats with one row per contact and the columns `row`,
+    ///   `column`, `min_distance` and `atom_contacts`.
+    pub fn write_sparse<W: Write>(
+        &self,
+        mut writer: W,
+        format: ContactMapFormat,
+    ) -> Result<(), PdbHandlerError> {
+        match format {
+            ContactMapFormat::Csv => {
+                writeln!(
+                    writer,
+                    "chain_a,residue_a,name_a,chain_b,residue_b,name_b,min_distance,atom_contacts"
+                )?;
+                for entry in &self.contacts {
+                    let residue_a = &self.residues_a[entry.row];
+                    let residue_b = &self.residues_b[entry.column];
+                    writeln!(
+                        writer,
+                        "{},{},{},{},{},{},{:.3},{}",
+                        self.chain_a,
+                        residue_label(residue_a),
+                        residue_a.name,
+                        self.chain_b,
+                        residue_label(residue_b),
+                        residue_b.name,
+                        entry.min_distance,
+                        entry.atom_contacts
+                    )?;
+                }
+            }
+            ContactMapFormat::Json => serde_json::to_writer(&mut writer, self)?,
+            ContactMapFormat::Npy => {
+                write_npy_header(&mut writer, "<f8", &[self.contacts.len(), 4])?;
+                for entry in &self.contacts {
+                    for value in [
+                        entry.row as f64,
+                        entry.column as f64,
+                        entry.min_distance,
+                        entry.atom_contacts as f64,
+                    ] {
+                        writer.write_all(&value.to_le_bytes())?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Identifies every pair of residues from different chains in contact.
+///
+/// Two residues are in contact when at least one pair of their atoms is within the cutoff. Only
+/// the atoms selected by `options` are considered.
+///
+/// # Arguments
+///
+/// * `structure` - A reference to a `pdbtbx::PDB` structure representing the PDB file to be analyzed.
+/// * `options` - The distance cutoff and the atoms to consider, see [`ContactOptions`].
+///
+/// # Returns
+///
+/// A `Vec<ResidueContact>` with the minimum distance and the number of atom contacts of each
+/// residue pair. Contacts are ordered by chain pair, in order of appearance, then by the order of
+/// the residues in their chains.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::{identify_residue_contacts, ContactOptions};
+///
+/// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+/// let contacts = identify_residue_contacts(&pdb, ContactOptions::default().set_cutoff(3.9));
+///
+/// for contact in contacts {
+///     println!(
+///         "{}{} - {}{}: {:.2} Å",
+///         contact.chain_a,
+///         contact.residue_a.serial,
+///         contact.chain_b,
+///         contact.residue_b.serial,
+///         contact.min_distance
+///     );
+/// }
+/// ```
+///
+/// # Notes
+///
+/// Residues without a name are skipped; use [`crate::identify_nameless_residues`] to report them.
+pub fn identify_residue_contacts(
+    structure: &pdbtbx::PDB,
+    options: &ContactOptions,
+) -> Vec<ResidueContact> {
+    residue_contact_maps(structure, options)
+        .into_iter()
+        .flat_map(|map| {
+            map.contacts
+                .into_iter()
+                .map(|entry| ResidueContact {
+                    chain_a: map.chain_a.clone(),
+                    residue_a: map.residues_a[entry.row].clone(),
+                    chain_b: map.chain_b.clone(),
+                    residue_b: map.residues_b[entry.column].clone(),
+                    min_distance: entry.min_distance,
+                    atom_contacts: entry.atom_contacts,
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Builds the residue contact map of each pair of chains in contact.
+///
+/// # Arguments
+///
+/// * `structure` - A reference to a `pdbtbx::PDB` structure representing the PDB file to be analyzed.
+/// * `options` - The distance cutoff and the atoms to consider, see [`ContactOptions`].
+///
+/// # Returns
+///
+/// A `Vec<ContactMap>` with one map per pair of chains in contact, ordered by the appearance of
+/// their chains in the structure.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::{residue_contact_maps, ContactMapFormat, ContactOptions};
+///
+/// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+///
+/// for map in residue_contact_maps(&pdb, &ContactOptions::default()) {
+///     let mut csv = Vec::new();
+///     map.write_sparse(&mut csv, ContactMapFormat::Csv).unwrap();
+/// }
+/// ```
+pub fn residue_contact_maps(structure: &pdbtbx::PDB, options: &ContactOptions) -> Vec<ContactMap> {
+    let chain_order = chain_order(structure);
+    // The minimum distance and the atom contacts of each residue pair, keyed by chain pair
+    let mut pairs: HashMap<(&str, &str), ResiduePairs> = HashMap::new();
+
+    for contact in atom_contacts(structure, &chain_order, options) {
+        let residue_pair = pairs
+            .entry((contact.chain_a, contact.chain_b))
+            .or_default()
+            .entry((contact.residue_a, contact.residue_b))
+            .or_insert((f64::INFINITY, 0));
+        residue_pair.0 = residue_pair.0.min(contact.distance);
+        residue_pair.1 += 1;
+    }
+
+    let residues = named_chain_residues(structure);
+    let mut pairs: Vec<_> = pairs.into_iter().collect();
+    pairs.sort_by_key(|((chain_a, chain_b), _)| (chain_order[chain_a], chain_order[chain_b]));
+
+    pairs
+        .into_iter()
+        .filter_map(|((chain_a, chain_b), contacts)| {
+            let (ids_a, residues_a): (Vec<_>, Vec<_>) = residues[chain_a].iter().cloned().unzip();
+            let (ids_b, residues_b): (Vec<_>, Vec<_>) = residues[chain_b].iter().cloned().unzip();
+            let index = |ids: Vec<ResidueId>| -> HashMap<ResidueId, usize> {
+                ids.into_iter().enumerate().map(|(i, id)| (id, i)).collect()
+            };
+            let (index_a, index_b) = (index(ids_a), index(ids_b));
+
+            // Residues without a name are not in the index and are skipped
+            let contacts: BTreeMap<(usize, usize), (f64, usize)> = contacts
+                .into_iter()
+                .filter_map(|((res_a, res_b), contact)| {
+                    let row = *index_a.get(&res_a)?;
+                    let column = *index_b.get(&res_b)?;
+                    Some(((row, column), contact))
+                })
+                .collect();
+            if contacts.is_empty() {
+                return None;
+            }
+
+            Some(ContactMap {
+                chain_a: chain_a.to_string(),
+                chain_b: chain_b.to_string(),
+                residues_a,
+                residues_b,
+                contacts: contacts
+                    .into_iter()
+                    .map(
+                        |((row, column), (min_distance, atom_contacts))| ContactMapEntry {
+                            row,
+                            column,
+                            min_distance,
+                            atom_contacts,
+                        },
+                    )
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// The minimum distance and the number of atom contacts of each residue pair of two chains.
+type ResiduePairs = HashMap<(ResidueId, ResidueId), (f64, usize)>;
+
+/// Returns the residue number with its insertion code, e.g. `52A`.
+fn residue_label(residue: &InterfaceResidue) -> String {
+    format!(
+        "{}{}",
+        residue.serial,
+        residue.insertion_code.as_deref().unwrap_or("")
+    )
+}
+
+/// Writes the header of a version 1.0 NumPy `.npy` file for a C-ordered array.
+fn write_npy_header<W: Write>(
+    writer: &mut W,
+    descr: &str,
+    shape: &[usize],
+) -> Result<(), PdbHandlerError> {
+    let shape: Vec<String> = shape.iter().map(|n| n.to_string()).collect();
+    let shape = match shape.len() {
+        1 => format!("({},)", shape[0]),
+        _ => format!("({})", shape.join(", ")),
+    };
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}",
+        descr, shape
+    );
+
+    // The magic string, version and header length take 10 bytes, and the header ends with a
+    // newline, so that the data starts at a multiple of 64 bytes
+    let padding = (64 - (10 + header.len() + 1) % 64) % 64;
+    header.push_str(&" ".repeat(padding));
+    header.push('\n');
+
+    writer.write_all(b"\x93NUMPY\x01\x00")?;
+    writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    writer.write_all(header.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use pdbtbx::ReadOptions;
+
+    fn structure() -> pdbtbx::PDB {
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/contact_map.pdb")
+            .unwrap();
+        structure
+    }
+
+    #[test]
+    fn test_identify_residue_contacts() {
+        let contacts =
+            identify_residue_contacts(&structure(), ContactOptions::default().set_cutoff(4.5));
+
+        assert_eq!(contacts.len(), 2);
+        assert_eq!(contacts[0].residue_a.serial, 2);
+        assert_eq!(contacts[0].residue_b.serial, 1);
+        assert_eq!(contacts[0].residue_b.insertion_code, None);
+        assert_eq!(contacts[0].atom_contacts, 2);
+        assert!((contacts[0].min_distance - 4.0).abs() < 1e-6);
+        assert_eq!(contacts[1].residue_b.insertion_code, Some("A".to_string()));
+        assert_eq!(contacts[1].atom_contacts, 1);
+        assert!((contacts[1].min_distance - 1.7_f64.hypot(4.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_contact_map_export() {
+        let maps = residue_contact_maps(&structure(), ContactOptions::default().set_cutoff(4.5));
+        assert_eq!(maps.len(), 1);
+        let map = &maps[0];
+
+        assert_eq!(map.dense(), vec![vec![0, 0], vec![2, 1]]);
+        assert_eq!(map.contacts.len(), 2);
+
+        let mut csv = Vec::new();
+        map.write_dense(&mut csv, ContactMapFormat::Csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "residue,1,1A\n1,0,0\n2,2,1\n"
+        );
+
+        let mut csv = Vec::new();
+        map.write_sparse(&mut csv, ContactMapFormat::Csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap().lines().nth(1).unwrap(),
+            "A,2,GLY,B,1,ALA,4.000,2"
+        );
+
+        let mut json = Vec::new();
+        map.write_sparse(&mut json, ContactMapFormat::Json).unwrap();
+        let parsed: ContactMap = serde_json::from_slice(&json).unwrap();
+        assert_eq!(&parsed, map);
+
+        let mut json = Vec::new();
+        map.write_dense(&mut json, ContactMapFormat::Json).unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(parsed["atom_contacts"], serde_json::json!([[0, 0], [2, 1]]));
+    }
+
+    #[test]
+    fn test_contact_map_npy() {
+        let maps = residue_contact_maps(&structure(), ContactOptions::default().set_cutoff(4.5));
+
+        let mut npy = Vec::new();
+        maps[0]
+            .write_dense(&mut npy, ContactMapFormat::Npy)
+            .unwrap();
+
+        assert!(npy.starts_with(b"\x93NUMPY\x01\x00"));
+        let header_len = u16::from_le_bytes([npy[8], npy[9]]) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+        let header = std::str::from_utf8(&npy[10..10 + header_len]).unwrap();
+        assert!(header.contains("'descr': '<i8'"));
+        assert!(header.contains("'shape': (2, 2)"));
+        assert!(header.ends_with('\n'));
+
+        let data: Vec<i64> = npy[10 + header_len..]
+            .chunks(8)
+            .map(|bytes| i64::from_le_bytes(bytes.try_into().unwrap()))
+            .collect();
+        assert_eq!(data, vec![0, 0, 2, 1]);
+
+        let mut npy = Vec::new();
+        maps[0]
+            .write_sparse(&mut npy, ContactMapFormat::Npy)
+            .unwrap();
+        let header_len = u16::from_le_bytes([npy[8], npy[9]]) as usize;
+        let data: Vec<f64> = npy[10 + header_len..]
+            .chunks(8)
+            .map(|bytes| f64::from_le_bytes(bytes.try_into().unwrap()))
+            .collect();
+        assert_eq!(data.len(), 8);
+        assert_eq!(data[..4], [1.0, 0.0, 4.0, 2.0]);
+    }
+}
@@ -0,0 +1,605 @@
+use crate::residue_id::chain_residues;
+use crate::ResidueId;
+use pdbtbx::{
+    ContainsAtomConformer, ContainsAtomConformerResidue, ContainsAtomConformerResidueChain,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Options for the contact analysis of [`chains_in_contact_with`].
+///
+/// The default options match [`chains_in_contact`]: a 5.0 Å cutoff between any pair of atoms.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::ContactOptions;
+///
+/// // Heavy-atom contacts within 3.9 Å
+/// let mut heavy = ContactOptions::default();
+/// heavy.set_cutoff(3.9).set_heavy_atoms_only(true);
+///
+/// // CA-CA neighbourhoods within 10 Å
+/// let mut ca = ContactOptions::default();
+/// ca.set_cutoff(10.0).set_atom_names(&["CA"]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContactOptions {
+    cutoff: f64,
+    heavy_atoms_only: bool,
+    exclude_hydrogens: bool,
+    atom_names: Option<Vec<String>>,
+}
+
+impl Default for ContactOptions {
+    fn default() -> Self {
+        ContactOptions {
+            cutoff: 5.0,
+            heavy_atoms_only: false,
+            exclude_hydrogens: false,
+            atom_names: None,
+        }
+    }
+}
+
+impl ContactOptions {
+    /// Sets the maximum distance, in Å, between two atoms in contact. Defaults to 5.0 Å.
+    pub fn set_cutoff(&mut self, cutoff: f64) -> &mut Self {
+        self.cutoff = cutoff;
+        self
+    }
+
+    /// Only considers atoms with a known element other than hydrogen. Atoms without an element
+    /// are ignored.
+    pub fn set_heavy_atoms_only(&mut self, heavy_atoms_only: bool) -> &mut Self {
+        self.heavy_atoms_only = heavy_atoms_only;
+        self
+    }
+
+    /// Ignores hydrogen atoms. Atoms without an element are considered hydrogens when their name
+    /// starts with `H`, optionally preceded by a digit (e.g. `1HG1`).
+    pub fn set_exclude_hydrogens(&mut self, exclude_hydrogens: bool) -> &mut Self {
+        self.exclude_hydrogens = exclude_hydrogens;
+        self
+    }
+
+    /// Only considers atoms with one of the given names, e.g. `CA` or `CB`.
+    pub fn set_atom_names<S: AsRef<str>>(&mut self, atom_names: &[S]) -> &mut Self {
+        self.atom_names = Some(
+            atom_names
+                .iter()
+                .map(|name| name.as_ref().trim().to_uppercase())
+                .collect(),
+        );
+        self
+    }
+
+    /// Returns the maximum distance, in Å, between two atoms in contact.
+    pub fn cutoff(&self) -> f64 {
+        self.cutoff
+    }
+
+    /// Returns true if the given atom is considered in the contact analysis.
+    pub fn includes(&self, atom: &pdbtbx::Atom) -> bool {
+        let is_hydrogen = |element: &pdbtbx::Element| *element == pdbtbx::Element::H;
+
+        if self.heavy_atoms_only && atom.element().is_none_or(is_hydrogen) {
+            return false;
+        }
+        if self.exclude_hydrogens {
+            let hydrogen = match atom.element() {
+                Some(element) => is_hydrogen(element),
+                None => atom
+                    .name()
+                    .trim_start_matches(|c: char| c.is_ascii_digit())
+                    .starts_with('H'),
+            };
+            if hydrogen {
+                return false;
+            }
+        }
+        match &self.atom_names {
+            Some(names) => names.iter().any(|name| name == atom.name()),
+            None => true,
+        }
+    }
+}
+
+/// A pair of chains in contact, as returned by [`chain_pairs_in_contact`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChainPair {
+    /// The ID of the first chain, in order of appearance in the structure.
+    pub chain_a: String,
+    /// The ID of the second chain.
+    pub chain_b: String,
+    /// The minimum distance, in Å, between the atoms of both chains.
+    pub min_distance: f64,
+}
+
+/// Identifies pairs of chains that are in close contact within the given PDB structure.
+///
+/// This function analyzes inter-chain interactions by checking for atoms from different chains
+/// that are within a specified distance threshold (5.0 Å). It returns a list of unique chain pairs
+/// where at least one pair of atoms from each chain is within the contact distance.
+///
+/// # Arguments
+///
+/// * `structure` - A reference to a `pdbtbx::PDB` structure representing the PDB file to be analyzed.
+///
+/// # Returns
+///
+/// A `Vec<(String, String)>` where each tuple represents a pair of chain IDs that are in contact.
+/// The pairs are unique (e.g., if (A, B) is present, (B, A) will not be included). Each pair is
+/// ordered by the appearance of its chains in the structure, and the list is sorted the same way.
+///
+/// # Example
+///
+/// ```rust
+/// use pdbtbx::PDB;
+/// use pdb_handler::chains_in_contact;
+///
+/// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+/// let contacting_chains = chains_in_contact(&pdb);
+///
+/// for (chain_a, chain_b) in contacting_chains {
+///     println!("Chains {} and {} are in contact", chain_a, chain_b);
+/// }
+/// ```
+///
+/// # Notes
+///
+/// - The contact distance threshold is fixed at 5.0 Å; use [`chains_in_contact_with`] for other
+///   cutoffs and atom selections.
+/// - Self-contacts (within the same chain) are ignored.
+/// - Atoms are looked up in an R*-tree, so the runtime grows roughly linearly with the number of
+///   atoms.
+/// - Use [`chain_pairs_in_contact`] to also get the minimum distance between the chains.
+pub fn chains_in_contact(structure: &pdbtbx::PDB) -> Vec<(String, String)> {
+    chains_in_contact_with(structure, &ContactOptions::default())
+}
+
+/// Identifies pairs of chains that are in close contact within the given PDB structure, using the
+/// given cutoff and atom selection.
+///
+/// # Arguments
+///
+/// * `structure` - A reference to a `pdbtbx::PDB` structure representing the PDB file to be analyzed.
+/// * `options` - The distance cutoff and the atoms to consider, see [`ContactOptions`].
+///
+/// # Returns
+///
+/// A `Vec<(String, String)>` where each tuple represents a pair of chain IDs that are in contact,
+/// ordered as in [`chains_in_contact`].
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::{chains_in_contact_with, ContactOptions};
+///
+/// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+/// let contacting_chains =
+///     chains_in_contact_with(&pdb, ContactOptions::default().set_cutoff(3.9).set_heavy_atoms_only(true));
+/// ```
+pub fn chains_in_contact_with(
+    structure: &pdbtbx::PDB,
+    options: &ContactOptions,
+) -> Vec<(String, String)> {
+    chain_pairs_in_contact(structure, options)
+        .into_iter()
+        .map(|pair| (pair.chain_a, pair.chain_b))
+        .collect()
+}
+
+/// Identifies pairs of chains that are in close contact within the given PDB structure, with the
+/// minimum distance between them.
+///
+/// # Arguments
+///
+/// * `structure` - A reference to a `pdbtbx::PDB` structure representing the PDB file to be analyzed.
+/// * `options` - The distance cutoff and the atoms to consider, see [`ContactOptions`].
+///
+/// # Returns
+///
+/// A `Vec<ChainPair>` with one entry per pair of chains in contact. The chains of each pair are
+/// ordered by their appearance in the structure, and the pairs are sorted the same way, e.g.
+/// `(A, B)`, `(A, C)`, `(B, C)`.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::{chain_pairs_in_contact, ContactOptions};
+///
+/// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+///
+/// for pair in chain_pairs_in_contact(&pdb, &ContactOptions::default()) {
+///     println!("{}-{}: {:.2} Å", pair.chain_a, pair.chain_b, pair.min_distance);
+/// }
+/// ```
+pub fn chain_pairs_in_contact(structure: &pdbtbx::PDB, options: &ContactOptions) -> Vec<ChainPair> {
+    let chain_order = chain_order(structure);
+    let mut contacts: HashMap<(&str, &str), f64> = HashMap::new();
+
+    for contact in atom_contacts(structure, &chain_order, options) {
+        let min_distance = contacts
+            .entry((contact.chain_a, contact.chain_b))
+            .or_insert(contact.distance);
+        *min_distance = min_distance.min(contact.distance);
+    }
+
+    let mut pairs: Vec<_> = contacts.into_iter().collect();
+    pairs.sort_by_key(|((chain_a, chain_b), _)| (chain_order[chain_a], chain_order[chain_b]));
+
+    pairs
+        .into_iter()
+        .map(|((chain_a, chain_b), min_distance)| ChainPair {
+            chain_a: chain_a.to_string(),
+            chain_b: chain_b.to_string(),
+            min_distance,
+        })
+        .collect()
+}
+
+/// Returns the position of each chain ID in order of first appearance in the structure.
+pub(crate) fn chain_order(structure: &pdbtbx::PDB) -> HashMap<&str, usize> {
+    let mut order: HashMap<&str, usize> = HashMap::new();
+    for chain in structure.chains() {
+        let next = order.len();
+        order.entry(chain.id()).or_insert(next);
+    }
+    order
+}
+
+/// A pair of atoms from different chains within the contact cutoff.
+pub(crate) struct AtomContact<'a> {
+    /// The chain of the first atom, which appears first in the structure.
+    pub chain_a: &'a str,
+    /// The residue of the first atom.
+    pub residue_a: ResidueId,
+    /// The chain of the second atom.
+    pub chain_b: &'a str,
+    /// The residue of the second atom.
+    pub residue_b: ResidueId,
+    /// The distance, in Å, between both atoms.
+    pub distance: f64,
+}
+
+/// Returns every pair of atoms selected by `options` from different chains within the cutoff.
+/// Atoms are looked up in an R*-tree, and each pair is returned once, ordered by `chain_order`.
+pub(crate) fn atom_contacts<'a>(
+    structure: &pdbtbx::PDB,
+    chain_order: &HashMap<&'a str, usize>,
+    options: &ContactOptions,
+) -> Vec<AtomContact<'a>> {
+    let tree = structure.create_hierarchy_rtree();
+    let max_distance_2 = options.cutoff * options.cutoff;
+    let mut contacts = Vec::new();
+
+    for hierarchy in tree.iter() {
+        let atom = hierarchy.atom();
+        let (chain, order) = chain_order.get_key_value(hierarchy.chain().id()).unwrap();
+        if !options.includes(atom) {
+            continue;
+        }
+
+        for neighbour in tree.locate_within_distance(atom.pos(), max_distance_2) {
+            let (other, other_order) = chain_order.get_key_value(neighbour.chain().id()).unwrap();
+            // Each pair of atoms is found from both sides, keep the one from the first chain
+            if order >= other_order || !options.includes(neighbour.atom()) {
+                continue;
+            }
+
+            contacts.push(AtomContact {
+                chain_a: chain,
+                residue_a: ResidueId::from_residue(hierarchy.residue()),
+                chain_b: other,
+                residue_b: ResidueId::from_residue(neighbour.residue()),
+                distance: atom.distance(neighbour.atom()),
+            });
+        }
+    }
+
+    contacts
+}
+
+/// Returns the named residues of each chain, with their IDs, in order of appearance. Residues
+/// without a name are skipped.
+pub(crate) fn named_chain_residues(
+    structure: &pdbtbx::PDB,
+) -> HashMap<&str, Vec<(ResidueId, InterfaceResidue)>> {
+    chain_residues(structure)
+        .into_iter()
+        .map(|(chain, residues)| {
+            let residues = residues
+                .into_iter()
+                .filter_map(|res| {
+                    let residue = InterfaceResidue {
+                        serial: res.serial_number(),
+                        insertion_code: res.insertion_code().map(|code| code.to_string()),
+                        name: res.name()?.to_string(),
+                    };
+                    Some((ResidueId::from_residue(res), residue))
+                })
+                .collect();
+            (chain, residues)
+        })
+        .collect()
+}
+
+/// A residue of a chain, as returned by [`identify_interface_residues`] and the residue contact
+/// functions.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InterfaceResidue {
+    /// The residue serial number.
+    pub serial: isize,
+    /// The residue insertion code, if any.
+    pub insertion_code: Option<String>,
+    /// The residue name.
+    pub name: String,
+}
+
+/// The interface between two chains in contact, as returned by [`identify_interface_residues`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChainInterface {
+    /// The ID of the first chain, in order of appearance in the structure.
+    pub chain_a: String,
+    /// The ID of the second chain.
+    pub chain_b: String,
+    /// The residues of the first chain within the cutoff of the second chain.
+    pub residues_a: Vec<InterfaceResidue>,
+    /// The residues of the second chain within the cutoff of the first chain.
+    pub residues_b: Vec<InterfaceResidue>,
+}
+
+/// Identifies the interface residues between each pair of chains in contact.
+///
+/// A residue is at the interface with another chain when at least one of its atoms is within the
+/// cutoff of an atom of that chain. Only the atoms selected by `options` are considered, so e.g.
+/// heavy-atom interfaces within 3.9 Å can be used to define docking restraints.
+///
+/// # Arguments
+///
+/// * `structure` - A reference to a `pdbtbx::PDB` structure representing the PDB file to be analyzed.
+/// * `options` - The distance cutoff and the atoms to consider, see [`ContactOptions`].
+///
+/// # Returns
+///
+/// A `Vec<ChainInterface>` with one entry per pair of chains in contact. Pairs are ordered by the
+/// appearance of their chains in the structure, and residues by their order in each chain.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::{identify_interface_residues, ContactOptions};
+///
+/// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+/// let interfaces = identify_interface_residues(&pdb, ContactOptions::default().set_cutoff(3.9));
+///
+/// for interface in interfaces {
+///     println!(
+///         "{}-{}: {} and {} residues",
+///         interface.chain_a,
+///         interface.chain_b,
+///         interface.residues_a.len(),
+///         interface.residues_b.len()
+///     );
+/// }
+/// ```
+///
+/// # Notes
+///
+/// Residues without a name are skipped; use [`crate::identify_nameless_residues`] to report them.
+pub fn identify_interface_residues(
+    structure: &pdbtbx::PDB,
+    options: &ContactOptions,
+) -> Vec<ChainInterface> {
+    let chain_order = chain_order(structure);
+    // The residues on each side of each pair of chains, keyed by the pair in order of appearance
+    type Side = HashSet<ResidueId>;
+    let mut interfaces: HashMap<(&str, &str), (Side, Side)> = HashMap::new();
+
+    for contact in atom_contacts(structure, &chain_order, options) {
+        let (side_a, side_b) = interfaces
+            .entry((contact.chain_a, contact.chain_b))
+            .or_default();
+        side_a.insert(contact.residue_a);
+        side_b.insert(contact.residue_b);
+    }
+
+    let residues = named_chain_residues(structure);
+    let interface_residues = |chain: &str, side: &Side| -> Vec<InterfaceResidue> {
+        residues[chain]
+            .iter()
+            .filter(|(id, _)| side.contains(id))
+            .map(|(_, residue)| residue.clone())
+            .collect()
+    };
+
+    let mut pairs: Vec<_> = interfaces.into_iter().collect();
+    pairs.sort_by_key(|((chain_a, chain_b), _)| (chain_order[chain_a], chain_order[chain_b]));
+
+    pairs
+        .into_iter()
+        .map(|((chain_a, chain_b), (side_a, side_b))| ChainInterface {
+            chain_a: chain_a.to_string(),
+            chain_b: chain_b.to_string(),
+            residues_a: interface_residues(chain_a, &side_a),
+            residues_b: interface_residues(chain_b, &side_b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use pdbtbx::ReadOptions;
+
+    #[test]
+    fn test_chains_in_contact() {
+        // Load the structure from the test_data folder
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/chains_in_contact.pdb")
+            .unwrap();
+
+        let contacts = chains_in_contact(&structure);
+
+        let expected = vec![("A".to_string(), "B".to_string())];
+
+        assert_eq!(contacts, expected);
+    }
+
+    #[test]
+    fn test_chains_in_contact_with() {
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/chains_in_contact.pdb")
+            .unwrap();
+        let expected = vec![("A".to_string(), "B".to_string())];
+
+        assert_eq!(
+            chains_in_contact_with(&structure, &ContactOptions::default()),
+            expected
+        );
+        assert!(
+            chains_in_contact_with(&structure, ContactOptions::default().set_cutoff(2.0))
+                .is_empty()
+        );
+
+        // The CA atoms are 6.3 Å apart
+        let mut options = ContactOptions::default();
+        options.set_atom_names(&["CA"]);
+        assert!(chains_in_contact_with(&structure, &options).is_empty());
+        assert_eq!(
+            chains_in_contact_with(&structure, options.set_cutoff(10.0)),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_chains_in_contact_hydrogens() {
+        // Only the hydrogens of both chains are within 5 Å
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/contacts_hydrogens.pdb")
+            .unwrap();
+
+        assert_eq!(chains_in_contact(&structure).len(), 1);
+        assert!(chains_in_contact_with(
+            &structure,
+            ContactOptions::default().set_exclude_hydrogens(true)
+        )
+        .is_empty());
+        assert!(chains_in_contact_with(
+            &structure,
+            ContactOptions::default().set_heavy_atoms_only(true)
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_identify_interface_residues() {
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/chains_in_contact.pdb")
+            .unwrap();
+
+        let interfaces = identify_interface_residues(&structure, &ContactOptions::default());
+
+        assert_eq!(interfaces.len(), 1);
+        assert_eq!(interfaces[0].chain_a, "A");
+        assert_eq!(interfaces[0].chain_b, "B");
+        assert_eq!(
+            interfaces[0].residues_a,
+            vec![InterfaceResidue {
+                serial: 933,
+                insertion_code: None,
+                name: "ASP".to_string(),
+            }]
+        );
+        assert_eq!(interfaces[0].residues_b[0].serial, 46);
+        assert_eq!(interfaces[0].residues_b[0].name, "ALA");
+
+        let mut options = ContactOptions::default();
+        options.set_atom_names(&["CA"]);
+        assert!(identify_interface_residues(&structure, &options).is_empty());
+    }
+
+    #[test]
+    fn test_identify_interface_residues_multiple() {
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/interfaces.pdb")
+            .unwrap();
+
+        let interfaces =
+            identify_interface_residues(&structure, ContactOptions::default().set_cutoff(4.5));
+        let serials = |residues: &[InterfaceResidue]| -> Vec<(isize, Option<String>)> {
+            residues
+                .iter()
+                .map(|res| (res.serial, res.insertion_code.clone()))
+                .collect()
+        };
+
+        assert_eq!(interfaces.len(), 2);
+        assert_eq!(
+            (
+                interfaces[0].chain_a.as_str(),
+                interfaces[0].chain_b.as_str()
+            ),
+            ("A", "B")
+        );
+        assert_eq!(
+            serials(&interfaces[0].residues_a),
+            vec![(2, None), (2, Some("A".to_string()))]
+        );
+        assert_eq!(
+            serials(&interfaces[0].residues_b),
+            vec![(1, None), (2, None)]
+        );
+        assert_eq!(
+            (
+                interfaces[1].chain_a.as_str(),
+                interfaces[1].chain_b.as_str()
+            ),
+            ("A", "C")
+        );
+        assert_eq!(
+            serials(&interfaces[1].residues_a),
+            vec![(2, Some("A".to_string()))]
+        );
+        assert_eq!(serials(&interfaces[1].residues_b), vec![(2, None)]);
+
+        let json = serde_json::to_string(&interfaces[1]).unwrap();
+        assert!(json.contains("\"chain_b\":\"C\""));
+    }
+
+    #[test]
+    fn test_chain_pairs_in_contact() {
+        // Chain C appears first and is in contact with both A and B
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/chain_pairs.pdb")
+            .unwrap();
+
+        let pairs = chain_pairs_in_contact(&structure, &ContactOptions::default());
+        let chains: Vec<(&str, &str)> = pairs
+            .iter()
+            .map(|pair| (pair.chain_a.as_str(), pair.chain_b.as_str()))
+            .collect();
+
+        assert_eq!(chains, vec![("C", "A"), ("C", "B")]);
+        assert!((pairs[0].min_distance - 5.0).abs() < 1e-6);
+        assert!((pairs[1].min_distance - 10.0_f64.sqrt()).abs() < 1e-6);
+
+        for _ in 0..10 {
+            assert_eq!(
+                chains_in_contact(&structure),
+                vec![
+                    ("C".to_string(), "A".to_string()),
+                    ("C".to_string(), "B".to_string())
+                ]
+            );
+        }
+    }
+}
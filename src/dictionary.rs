@@ -0,0 +1,216 @@
+use crate::constants::{AMINOACIDS, CARBOHYDRATES, DNA, IONS, MODIFIED_RESIDUES, RNA, WATER};
+use crate::{MolecularType, PdbHandlerError, ResidueLookup, StandardResidues};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+
+/// A user-extensible dictionary of residue names and their molecular types.
+///
+/// The default dictionary is seeded from the built-in residue tables (amino acids, nucleotides,
+/// modified residues, waters, ions and carbohydrates) and can be extended from code or from a
+/// JSON file mapping residue names to molecular types, e.g. `{"LIG": "Ligand", "HID": "Protein"}`.
+///
+/// The dictionary can be passed to the `*_with` functions (e.g. [`crate::identify_unknowns_with`]).
+///
+/// # Examples
+///
+/// ```
+/// use pdb_handler::{identify_unknowns_with, MolecularType, ResidueDictionary};
+///
+/// let mut dictionary = ResidueDictionary::default();
+/// dictionary.insert("I09", MolecularType::Ligand);
+///
+/// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+/// let unknown_residues = identify_unknowns_with(&pdb, &dictionary);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct ResidueDictionary {
+    residues: HashMap<String, MolecularType>,
+}
+
+impl Default for ResidueDictionary {
+    fn default() -> Self {
+        let residues = AMINOACIDS
+            .iter()
+            .chain(DNA.iter())
+            .chain(RNA.iter())
+            .chain(WATER.iter())
+            .chain(IONS.iter())
+            .chain(CARBOHYDRATES.iter())
+            .chain(MODIFIED_RESIDUES.iter().map(|(modified, _)| modified))
+            .filter_map(|name| {
+                StandardResidues
+                    .molecular_type(name)
+                    .map(|mol_type| (name.to_string(), mol_type))
+            })
+            .collect();
+
+        ResidueDictionary { residues }
+    }
+}
+
+impl ResidueDictionary {
+    /// Creates a dictionary without any residues, not even the built-in ones.
+    pub fn empty() -> Self {
+        ResidueDictionary {
+            residues: HashMap::new(),
+        }
+    }
+
+    /// Creates a dictionary seeded from the built-in residue tables and extended with the
+    /// residues from the given JSON file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - A string slice that holds the path to a JSON file mapping residue names to
+    ///   molecular types.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pdb_handler::{MolecularType, ResidueDictionary};
+    ///
+    /// let dictionary = ResidueDictionary::from_json_file("test_data/residue_dictionary.json").unwrap();
+    /// assert_eq!(dictionary.get("I09"), Some(&MolecularType::Ligand));
+    /// ```
+    pub fn from_json_file(path: &str) -> Result<Self, PdbHandlerError> {
+        let mut dictionary = ResidueDictionary::default();
+        dictionary.extend_from_json_file(path)?;
+        Ok(dictionary)
+    }
+
+    /// Extends the dictionary with the residues from the given JSON file, replacing the
+    /// molecular type of residues that are already present.
+    pub fn extend_from_json_file(&mut self, path: &str) -> Result<(), PdbHandlerError> {
+        let file = File::open(path)?;
+        let other: ResidueDictionary = serde_json::from_reader(BufReader::new(file))?;
+        self.extend(other.residues);
+        Ok(())
+    }
+
+    /// Adds a residue to the dictionary, returning its previous molecular type, if any.
+    pub fn insert(
+        &mut self,
+        res_name: impl Into<String>,
+        mol_type: MolecularType,
+    ) -> Option<MolecularType> {
+        self.residues
+            .insert(res_name.into().to_uppercase(), mol_type)
+    }
+
+    /// Removes a residue from the dictionary, returning its molecular type, if it was present.
+    pub fn remove(&mut self, res_name: &str) -> Option<MolecularType> {
+        self.residues.remove(&res_name.to_uppercase())
+    }
+
+    /// Returns the molecular type of the given residue, if present.
+    pub fn get(&self, res_name: &str) -> Option<&MolecularType> {
+        self.residues.get(&res_name.to_uppercase())
+    }
+
+    /// Returns true if the dictionary contains the given residue.
+    pub fn contains(&self, res_name: &str) -> bool {
+        self.residues.contains_key(&res_name.to_uppercase())
+    }
+
+    /// Returns the number of residues in the dictionary.
+    pub fn len(&self) -> usize {
+        self.residues.len()
+    }
+
+    /// Returns true if the dictionary contains no residues.
+    pub fn is_empty(&self) -> bool {
+        self.residues.is_empty()
+    }
+}
+
+impl<S: Into<String>> Extend<(S, MolecularType)> for ResidueDictionary {
+    fn extend<I: IntoIterator<Item = (S, MolecularType)>>(&mut self, iter: I) {
+        for (res_name, mol_type) in iter {
+            self.insert(res_name, mol_type);
+        }
+    }
+}
+
+// Residue names are looked up in uppercase, so the names read from JSON are normalised the same
+// way as in `insert`
+impl<'de> Deserialize<'de> for ResidueDictionary {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let residues = HashMap::<String, MolecularType>::deserialize(deserializer)?;
+        let mut dictionary = ResidueDictionary::empty();
+        dictionary.extend(residues);
+        Ok(dictionary)
+    }
+}
+
+impl ResidueLookup for ResidueDictionary {
+    fn molecular_type(&self, res_name: &str) -> Option<MolecularType> {
+        self.get(res_name).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{identify_molecular_types_with, identify_unknowns, identify_unknowns_with};
+    use pdbtbx::ReadOptions;
+
+    #[test]
+    fn test_default_dictionary() {
+        let dictionary = ResidueDictionary::default();
+
+        assert_eq!(dictionary.get("ALA"), Some(&MolecularType::Protein));
+        assert_eq!(dictionary.get("DA"), Some(&MolecularType::Dna));
+        assert_eq!(dictionary.get("MSE"), Some(&MolecularType::Protein));
+        assert_eq!(dictionary.get("hoh"), Some(&MolecularType::Water));
+        assert_eq!(dictionary.get("I09"), None);
+    }
+
+    #[test]
+    fn test_dictionary_from_json_file() {
+        let dictionary =
+            ResidueDictionary::from_json_file("test_data/residue_dictionary.json").unwrap();
+
+        assert_eq!(dictionary.get("I09"), Some(&MolecularType::Ligand));
+        assert_eq!(dictionary.get("HID"), Some(&MolecularType::Protein));
+        assert_eq!(dictionary.get("ALA"), Some(&MolecularType::Protein));
+    }
+
+    #[test]
+    fn test_dictionary_lowercase_json() {
+        let dictionary: ResidueDictionary =
+            serde_json::from_str(r#"{"lig": "Ligand", "Hid": "Protein"}"#).unwrap();
+
+        assert_eq!(dictionary.len(), 2);
+        assert_eq!(dictionary.get("LIG"), Some(&MolecularType::Ligand));
+        assert_eq!(dictionary.get("hid"), Some(&MolecularType::Protein));
+        assert!(dictionary.contains("HID"));
+        let json = serde_json::to_string(&dictionary).unwrap();
+        assert!(json.contains("\"LIG\""));
+    }
+
+    #[test]
+    fn test_identify_with_dictionary() {
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/prot_ligand.pdb")
+            .unwrap();
+
+        let mut dictionary = ResidueDictionary::default();
+        dictionary.insert("I09", MolecularType::Ligand);
+
+        let unknowns = identify_unknowns_with(&structure, &dictionary);
+        assert_eq!(identify_unknowns(&structure)["A"], vec!["I09".to_string()]);
+        assert!(unknowns["A"].is_empty());
+
+        dictionary.insert("VAL", MolecularType::Other);
+        let mol_types = identify_molecular_types_with(&structure, &dictionary);
+        assert_eq!(
+            mol_types["A"],
+            vec![MolecularType::Ligand, MolecularType::Other]
+        );
+    }
+}
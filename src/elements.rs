@@ -0,0 +1,233 @@
+use crate::constants::{IONS, TWO_LETTER_ELEMENTS};
+use crate::{classify_residue_name, MolecularType};
+
+/// Infers the element symbol of an atom from its name and the name of its residue.
+///
+/// The atom name can be given either as the four-character field of columns 13-16 of a PDB line,
+/// e.g. `" CA "` or `"FE  "`, or as a trimmed name, e.g. `"CA"`. The element is inferred as follows:
+///
+/// * Leading digits are skipped, e.g. `1HG1` is hydrogen.
+/// * Names that start with `H` and contain a digit are hydrogens, e.g. `HG11`.
+/// * Atoms of ions, and atoms named after their residue, use a two-letter symbol when there is
+///   one, e.g. `CA` in the `CA` ion is calcium.
+/// * Names that start with C, N, O or H followed by a remoteness letter (A, B, G, D, E, Z or H)
+///   are read as one-letter symbols in polymer residues (protein, DNA and RNA), e.g. `CA` in `ALA`
+///   is carbon and `HG` in `SER` is hydrogen.
+/// * For the four-character field, the PDB column alignment is used: two-letter symbols start in
+///   column 13 and one-letter symbols in column 14, e.g. `FE  ` in `HEM` is iron and ` NA ` in
+///   `HEM` is nitrogen.
+/// * For trimmed names, names with two letters that form a two-letter symbol are read as such,
+///   unless they are one of the ambiguous names above, e.g. `SE` in `MSE` is selenium and `CL1`
+///   is chlorine, but `NA` in `HEM` is nitrogen.
+/// * All other names use their first letter.
+///
+/// # Arguments
+///
+/// * `atom_name` - The atom name, as a four-character field or trimmed.
+/// * `res_name` - The name of the residue the atom belongs to.
+///
+/// # Returns
+///
+/// The element symbol in uppercase, as written in columns 77-78, or `None` if the atom name has
+/// no letters.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::infer_element;
+///
+/// assert_eq!(infer_element(" CA ", "ALA"), Some("C".to_string()));
+/// assert_eq!(infer_element("CA", "CA"), Some("CA".to_string()));
+/// assert_eq!(infer_element("FE  ", "HEM"), Some("FE".to_string()));
+/// assert_eq!(infer_element("SE", "MSE"), Some("SE".to_string()));
+/// ```
+pub fn infer_element(atom_name: &str, res_name: &str) -> Option<String> {
+    let res_name = res_name.trim().to_uppercase();
+    let name = atom_name.trim().to_uppercase();
+    let unnumbered = name.trim_start_matches(|c: char| c.is_ascii_digit());
+    let letters: String = unnumbered
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect();
+    if letters.is_empty() {
+        return None;
+    }
+    let first_letter = letters[..1].to_string();
+    let two_letters = letters.get(..2).filter(|s| TWO_LETTER_ELEMENTS.contains(s));
+
+    // Hydrogens are numbered, e.g. HG11, while mercury is not
+    if first_letter == "H" && name.contains(|c: char| c.is_ascii_digit()) {
+        return Some(first_letter);
+    }
+
+    // Ions are named after their element, e.g. CA or ZN
+    if IONS.contains(&res_name.as_str()) || name == res_name {
+        if let Some(symbol) = two_letters {
+            return Some(symbol.to_string());
+        }
+    }
+
+    let Some(symbol) = two_letters else {
+        return Some(first_letter);
+    };
+
+    // Names such as CA, CD, NE or HG name an atom by its position in the side chain
+    let ambiguous = matches!(&first_letter[..], "C" | "N" | "O" | "H")
+        && matches!(&letters[1..2], "A" | "B" | "G" | "D" | "E" | "Z" | "H");
+    let is_polymer = matches!(
+        classify_residue_name(&res_name),
+        Some(MolecularType::Protein | MolecularType::Dna | MolecularType::Rna)
+    );
+
+    let two_letter = if ambiguous && is_polymer {
+        false
+    } else if atom_name.len() == 4 {
+        atom_name.starts_with(|c: char| c.is_ascii_alphabetic())
+    } else {
+        letters.len() == 2 && !ambiguous
+    };
+    if two_letter {
+        Some(symbol.to_string())
+    } else {
+        Some(first_letter)
+    }
+}
+
+/// Fills in the element symbol (columns 77-78) of an `ATOM` or `HETATM` line if it is missing,
+/// padding the line to 80 characters. Returns the inferred element, if any.
+pub(crate) fn fill_missing_element(line: &mut String) -> Option<String> {
+    if !line.is_ascii() || line.get(76..78).is_some_and(|e| !e.trim().is_empty()) {
+        return None;
+    }
+    let element = infer_element(line.get(12..16)?, line.get(17..20)?)?;
+
+    if line.len() < 80 {
+        *line = format!("{:<80}", line);
+    }
+    line.replace_range(76..78, &format!("{:>2}", element));
+    Some(element)
+}
+
+/// Infers the element of every atom in the given PDB structure that has none.
+///
+/// When columns 77-78 are empty, pdbtbx guesses the element from the full atom name, or from its
+/// first letter if that is C, H, N, O or S, and leaves it empty otherwise, e.g. for `FE1` or
+/// `1HG1`. This function fills in the remaining elements from the atom and residue names; see
+/// [`infer_element`] for the rules used. Elements that are already set are kept. Atom names in a
+/// `pdbtbx::PDB` are trimmed, so the column alignment of the original file is not available, and
+/// pdbtbx's guesses, such as calcium for `CA` in `ALA`, cannot be told apart from the elements
+/// given in the file. To avoid both, fill in the missing elements on the text before parsing it,
+/// with [`crate::PdbLineFilter::infer_elements`] or [`crate::tidy`].
+///
+/// # Arguments
+///
+/// * `structure` - A mutable reference to a `pdbtbx::PDB` structure.
+///
+/// # Returns
+///
+/// The number of atoms whose element was filled in.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::{infer_elements, try_pad_lines};
+///
+/// let reader = try_pad_lines("example-pdbs/dna.pdb").unwrap();
+/// let (mut pdb, _errors) = pdbtbx::ReadOptions::default()
+///     .set_format(pdbtbx::Format::Pdb)
+///     .read_raw(reader)
+///     .unwrap();
+/// let filled = infer_elements(&mut pdb);
+///
+/// assert!(pdb.atoms().all(|atom| atom.element().is_some()));
+/// ```
+pub fn infer_elements(structure: &mut pdbtbx::PDB) -> usize {
+    let mut filled = 0;
+
+    for residue in structure.residues_mut() {
+        let res_name = residue.name().unwrap_or("").to_string();
+        for atom in residue.atoms_mut().filter(|atom| atom.element().is_none()) {
+            let element =
+                infer_element(atom.name(), &res_name).and_then(pdbtbx::Element::from_symbol);
+            if let Some(element) = element {
+                atom.set_element(element);
+                filled += 1;
+            }
+        }
+    }
+
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::try_pad_lines;
+    use pdbtbx::ReadOptions;
+
+    #[test]
+    fn test_infer_element() {
+        assert_eq!(infer_element(" CA ", "ALA"), Some("C".to_string()));
+        assert_eq!(infer_element("CA", "ALA"), Some("C".to_string()));
+        assert_eq!(infer_element("CA  ", "ALA"), Some("C".to_string()));
+        assert_eq!(infer_element("CA  ", "CA"), Some("CA".to_string()));
+        assert_eq!(infer_element("CA", "CA"), Some("CA".to_string()));
+        assert_eq!(infer_element("FE  ", "HEM"), Some("FE".to_string()));
+        assert_eq!(infer_element("FE", "HEM"), Some("FE".to_string()));
+        assert_eq!(infer_element(" NA ", "HEM"), Some("N".to_string()));
+        assert_eq!(infer_element("NA", "HEM"), Some("N".to_string()));
+        assert_eq!(infer_element("HG11", "VAL"), Some("H".to_string()));
+        assert_eq!(infer_element("1HG1", "VAL"), Some("H".to_string()));
+        assert_eq!(infer_element("HG", "SER"), Some("H".to_string()));
+        assert_eq!(infer_element(" O5'", "DA"), Some("O".to_string()));
+        assert_eq!(infer_element("    ", "VAL"), None);
+    }
+
+    #[test]
+    fn test_infer_element_two_letters() {
+        assert_eq!(infer_element("SE", "MSE"), Some("SE".to_string()));
+        assert_eq!(infer_element("SE  ", "MSE"), Some("SE".to_string()));
+        assert_eq!(infer_element(" SD ", "MET"), Some("S".to_string()));
+        assert_eq!(infer_element("CL1", "LIG"), Some("CL".to_string()));
+        assert_eq!(infer_element("CL1 ", "LIG"), Some("CL".to_string()));
+        assert_eq!(infer_element("BR", "LIG"), Some("BR".to_string()));
+        assert_eq!(infer_element("CA1", "LIG"), Some("C".to_string()));
+        assert_eq!(infer_element("HG11", "LIG"), Some("H".to_string()));
+        assert_eq!(infer_element("HG", "HG"), Some("HG".to_string()));
+    }
+
+    #[test]
+    fn test_infer_elements() {
+        let reader = try_pad_lines("test_data/dna.pdb").unwrap();
+        let (mut structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read_raw(reader)
+            .unwrap();
+
+        assert_eq!(infer_elements(&mut structure), 0);
+        assert!(structure.atoms().all(|atom| atom.element().is_some()));
+
+        let reader = try_pad_lines("test_data/elements.pdb").unwrap();
+        let (mut structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read_raw(reader)
+            .unwrap();
+        let element = |structure: &pdbtbx::PDB, serial: usize| {
+            structure
+                .atoms()
+                .find(|atom| atom.serial_number() == serial)
+                .and_then(|atom| atom.element().cloned())
+        };
+        assert_eq!(element(&structure, 3), None);
+        assert_eq!(element(&structure, 4), None);
+
+        // Only the missing elements are filled in
+        assert_eq!(infer_elements(&mut structure), 2);
+        assert_eq!(element(&structure, 1), Some(pdbtbx::Element::Se));
+        assert_eq!(element(&structure, 2), Some(pdbtbx::Element::Cl));
+        assert_eq!(element(&structure, 3), Some(pdbtbx::Element::H));
+        assert_eq!(element(&structure, 4), Some(pdbtbx::Element::Fe));
+        assert_eq!(element(&structure, 5), Some(pdbtbx::Element::Br));
+    }
+}
@@ -0,0 +1,281 @@
+use crate::alignment::{needleman_wunsch, residue_name_score};
+use crate::{identify_residue_types, MolecularType, ResidueAnnotation};
+use serde::{Deserialize, Serialize};
+
+/// The minimum fraction of the residues of the longer of two polymer segments that must be aligned
+/// to residues of the other segment for both to be the same entity.
+pub const MIN_ENTITY_COVERAGE: f64 = 0.7;
+
+/// The type of an entity, following the `_entity.type` values of mmCIF.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EntityType {
+    Polymer,
+    NonPolymer,
+    Water,
+}
+
+/// A single occurrence of an entity in the structure, spanning a range of residues of one chain.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EntityInstance {
+    /// The ID of the chain the instance belongs to.
+    pub chain: String,
+    /// The serial number of the first residue of the instance.
+    pub start: isize,
+    /// The insertion code of the first residue of the instance, if any.
+    pub start_insertion_code: Option<String>,
+    /// The serial number of the last residue of the instance.
+    pub end: isize,
+    /// The insertion code of the last residue of the instance, if any.
+    pub end_insertion_code: Option<String>,
+}
+
+/// A distinct molecular entity, similar to an `_entity` record in mmCIF.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Entity {
+    /// The entity ID, starting at 1 in order of first appearance.
+    pub id: usize,
+    /// Whether the entity is a polymer, a non-polymer or water.
+    pub entity_type: EntityType,
+    /// The molecular type of the residues of the entity.
+    pub mol_type: MolecularType,
+    /// The residue names of the entity; a single name for non-polymers and water.
+    pub sequence: Vec<String>,
+    /// All occurrences of the entity in the structure.
+    pub instances: Vec<EntityInstance>,
+}
+
+/// Identifies the polymer, non-polymer and water entities in the given PDB structure.
+///
+/// Residues of each chain are grouped into segments: consecutive polymer residues (protein, DNA
+/// or RNA) of the same molecular type form a polymer segment, each remaining residue forms a
+/// non-polymer instance and all waters of a chain form a single water instance. This splits chains
+/// that hold a polymer together with its ligands and waters.
+///
+/// Polymer segments are assigned to the same entity when their sequence identity is at least
+/// `min_identity`. The identity is the fraction of identical residues in a global alignment of the
+/// sequences, relative to the length of the shortest sequence, so that a copy with missing
+/// terminal residues is the same entity. The alignment must also cover at least
+/// [`MIN_ENTITY_COVERAGE`] of the longer sequence, so that a short peptide is not the same entity
+/// as every chain that contains its sequence. Non-polymers are grouped by residue name.
+///
+/// # Arguments
+///
+/// * `structure` - A reference to a `pdbtbx::PDB` structure representing the PDB file to be analyzed.
+/// * `min_identity` - The minimum sequence identity (between 0.0 and 1.0) for two polymer segments
+///   to be considered the same entity.
+///
+/// # Returns
+///
+/// A `Vec<Entity>` ordered by first appearance in the structure.
+///
+/// # Example
+///
+/// ```rust
+/// use pdbtbx::PDB;
+/// use pdb_handler::identify_entities;
+///
+/// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+/// let entities = identify_entities(&pdb, 1.0);
+///
+/// for entity in entities {
+///     println!("Entity {} ({:?}): {} instances", entity.id, entity.entity_type, entity.instances.len());
+/// }
+/// ```
+///
+/// # Notes
+///
+/// Residues without a name are skipped; use [`crate::identify_nameless_residues`] to report them.
+pub fn identify_entities(structure: &pdbtbx::PDB, min_identity: f64) -> Vec<Entity> {
+    let mut entities: Vec<Entity> = Vec::new();
+
+    for segment in entity_segments(&identify_residue_types(structure)) {
+        let first = segment[0];
+        let last = segment[segment.len() - 1];
+        let entity_type = match first.mol_type {
+            MolecularType::Protein | MolecularType::Dna | MolecularType::Rna => EntityType::Polymer,
+            MolecularType::Water => EntityType::Water,
+            _ => EntityType::NonPolymer,
+        };
+        let sequence: Vec<String> = match entity_type {
+            EntityType::Polymer => segment.iter().map(|res| res.name.clone()).collect(),
+            _ => vec![first.name.clone()],
+        };
+        let instance = EntityInstance {
+            chain: first.chain.clone(),
+            start: first.serial,
+            start_insertion_code: first.insertion_code.clone(),
+            end: last.serial,
+            end_insertion_code: last.insertion_code.clone(),
+        };
+
+        let existing = entities.iter_mut().find(|entity| {
+            entity.entity_type == entity_type
+                && entity.mol_type == first.mol_type
+                && match entity_type {
+                    EntityType::Polymer => {
+                        sequence_identity(&entity.sequence, &sequence) >= min_identity
+                    }
+                    EntityType::NonPolymer => entity.sequence == sequence,
+                    EntityType::Water => true,
+                }
+        });
+
+        match existing {
+            Some(entity) => entity.instances.push(instance),
+            None => entities.push(Entity {
+                id: entities.len() + 1,
+                entity_type,
+                mol_type: first.mol_type.clone(),
+                sequence,
+                instances: vec![instance],
+            }),
+        }
+    }
+
+    entities
+}
+
+/// Splits the residues of each chain into polymer segments, non-polymer residues and one water
+/// segment per chain.
+fn entity_segments(residues: &[ResidueAnnotation]) -> Vec<Vec<&ResidueAnnotation>> {
+    let mut segments: Vec<Vec<&ResidueAnnotation>> = Vec::new();
+    let mut waters: Vec<&ResidueAnnotation> = Vec::new();
+
+    for (i, res) in residues.iter().enumerate() {
+        let new_chain = i == 0 || residues[i - 1].chain != res.chain;
+        if new_chain && !waters.is_empty() {
+            segments.push(std::mem::take(&mut waters));
+        }
+
+        match res.mol_type {
+            MolecularType::Water => waters.push(res),
+            MolecularType::Protein | MolecularType::Dna | MolecularType::Rna => {
+                let extends_previous = !new_chain && residues[i - 1].mol_type == res.mol_type;
+                match segments.last_mut() {
+                    Some(segment) if extends_previous => segment.push(res),
+                    _ => segments.push(vec![res]),
+                }
+            }
+            _ => segments.push(vec![res]),
+        }
+    }
+    if !waters.is_empty() {
+        segments.push(waters);
+    }
+
+    segments
+}
+
+/// Returns the fraction of identical residues in the global alignment of both sequences, relative
+/// to the length of the shortest sequence, or 0.0 if the aligned residues cover less than
+/// [`MIN_ENTITY_COVERAGE`] of the longest sequence.
+fn sequence_identity(a: &[String], b: &[String]) -> f64 {
+    let length = a.len().min(b.len());
+    if length == 0 {
+        return if a.len() == b.len() { 1.0 } else { 0.0 };
+    }
+    let (_, columns) = needleman_wunsch(a, b, |x, y| residue_name_score(x, y));
+    let aligned: Vec<(usize, usize)> = columns
+        .into_iter()
+        .filter_map(|column| match column {
+            (Some(i), Some(j)) => Some((i, j)),
+            _ => None,
+        })
+        .collect();
+    if (aligned.len() as f64) < MIN_ENTITY_COVERAGE * a.len().max(b.len()) as f64 {
+        return 0.0;
+    }
+    let identical = aligned.iter().filter(|(i, j)| a[*i] == b[*j]).count();
+    identical as f64 / length as f64
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use pdbtbx::ReadOptions;
+
+    fn entity_chains(entity: &Entity) -> Vec<&str> {
+        entity
+            .instances
+            .iter()
+            .map(|instance| instance.chain.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_identify_entities() {
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/entities.pdb")
+            .unwrap();
+
+        let entities = identify_entities(&structure, 1.0);
+
+        assert_eq!(entities.len(), 4);
+
+        assert_eq!(entities[0].entity_type, EntityType::Polymer);
+        assert_eq!(entities[0].sequence, vec!["ALA", "GLY", "SER"]);
+        assert_eq!(entity_chains(&entities[0]), vec!["A", "B"]);
+        assert_eq!(entities[0].instances[0].start, 1);
+        assert_eq!(entities[0].instances[0].end, 3);
+
+        assert_eq!(entities[1].entity_type, EntityType::NonPolymer);
+        assert_eq!(entities[1].mol_type, MolecularType::Ion);
+        assert_eq!(entities[1].sequence, vec!["ZN"]);
+
+        assert_eq!(entities[2].entity_type, EntityType::Water);
+        assert_eq!(entity_chains(&entities[2]), vec!["A", "B"]);
+        assert_eq!(entities[2].instances[1].start, 201);
+        assert_eq!(entities[2].instances[1].end, 202);
+
+        assert_eq!(entities[3].id, 4);
+        assert_eq!(entities[3].sequence, vec!["ALA", "GLY", "THR"]);
+        assert_eq!(entity_chains(&entities[3]), vec!["C"]);
+    }
+
+    #[test]
+    fn test_identify_entities_min_identity() {
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/entities.pdb")
+            .unwrap();
+
+        let entities = identify_entities(&structure, 0.6);
+
+        assert_eq!(entities.len(), 3);
+        assert_eq!(entity_chains(&entities[0]), vec!["A", "B", "C"]);
+    }
+
+    #[test]
+    fn test_identify_entities_truncated() {
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/entities_truncated.pdb")
+            .unwrap();
+
+        let entities = identify_entities(&structure, 1.0);
+
+        assert_eq!(entities.len(), 2);
+        assert_eq!(entities[0].sequence, vec!["ALA", "GLY", "SER", "LYS"]);
+        assert_eq!(entity_chains(&entities[0]), vec!["A", "B"]);
+        assert_eq!(entities[0].instances[1].start, 2);
+        assert_eq!(entity_chains(&entities[1]), vec!["C"]);
+    }
+
+    #[test]
+    fn test_identify_entities_peptide() {
+        // Chain B is a peptide matching the start of chain A, chain C misses its last residue
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/entities_peptide.pdb")
+            .unwrap();
+
+        let entities = identify_entities(&structure, 1.0);
+
+        assert_eq!(entities.len(), 2);
+        assert_eq!(entity_chains(&entities[0]), vec!["A", "C"]);
+        assert_eq!(entities[1].sequence, vec!["ALA", "GLY"]);
+        assert_eq!(entity_chains(&entities[1]), vec!["B"]);
+    }
+}
@@ -0,0 +1,48 @@
+use std::fmt;
+use std::io;
+
+/// The error type for the fallible functions of this crate.
+#[derive(Debug)]
+pub enum PdbHandlerError {
+    /// An I/O error, e.g. a file that cannot be opened or read.
+    Io(io::Error),
+    /// A line that is not valid UTF-8. The line number starts at 1.
+    Encoding { line: usize },
+    /// Content that cannot be parsed, with a description of the problem.
+    Parse(String),
+}
+
+impl fmt::Display for PdbHandlerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PdbHandlerError::Io(e) => write!(f, "I/O error: {}", e),
+            PdbHandlerError::Encoding { line } => write!(f, "invalid UTF-8 on line {}", line),
+            PdbHandlerError::Parse(message) => write!(f, "parse error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for PdbHandlerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PdbHandlerError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PdbHandlerError {
+    fn from(e: io::Error) -> Self {
+        PdbHandlerError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for PdbHandlerError {
+    fn from(e: serde_json::Error) -> Self {
+        if e.is_io() {
+            PdbHandlerError::Io(e.into())
+        } else {
+            PdbHandlerError::Parse(e.to_string())
+        }
+    }
+}
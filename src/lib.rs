@@ -1,16 +1,60 @@
-use crate::constants::{AMINOACIDS, DNA};
+use crate::constants::{AMINOACIDS, CARBOHYDRATES, DNA, IONS, MODIFIED_RESIDUES, RNA, WATER};
 use serde::{Deserialize, Serialize};
 use std::fs::File;
-use std::io::{BufRead, BufReader, Cursor};
+use std::io::{BufRead, BufReader, Cursor, Read, Write};
 
 use std::collections::{HashMap, HashSet};
 
+mod alignment;
+mod ccd;
 mod constants;
+mod contact_map;
+mod contacts;
+mod dictionary;
+mod elements;
+mod entities;
+mod error;
+mod lines;
+mod ranges;
+mod records;
+mod renumber;
+mod residue_id;
+mod segments;
+mod tidy;
+
+pub use alignment::{align_chains, renumber_to_reference, ChainAlignment};
+pub use ccd::{Component, ComponentRegistry};
+pub use contact_map::{
+    identify_residue_contacts, residue_contact_maps, ContactMap, ContactMapEntry, ContactMapFormat,
+    ResidueContact,
+};
+pub use contacts::{
+    chain_pairs_in_contact, chains_in_contact, chains_in_contact_with, identify_interface_residues,
+    ChainInterface, ChainPair, ContactOptions, InterfaceResidue,
+};
+pub use dictionary::ResidueDictionary;
+pub use elements::{infer_element, infer_elements};
+pub use entities::{identify_entities, Entity, EntityInstance, EntityType, MIN_ENTITY_COVERAGE};
+pub use error::PdbHandlerError;
+pub use lines::{PdbLineFilter, PdbLineReader, COORDINATE_RECORDS};
+pub use ranges::{ResidueRange, ResidueRanges};
+pub use records::RecordFilter;
+pub use renumber::{renumber_offset, renumber_sequential, renumber_with, ResidueMapping};
+pub use residue_id::{identify_residue_ids, ResidueId, ResidueOrder};
+pub use segments::{
+    identify_chain_segments, ChainSegments, GapKind, ResidueGap, Segment, MAX_BOND_LENGTH,
+};
+pub use tidy::{tidy, TidyFix};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum MolecularType {
     Protein,
     Dna,
+    Rna,
+    Carbohydrate,
+    Ligand,
+    Ion,
+    Water,
     Other,
 }
 
@@ -19,16 +63,58 @@ impl From<MolecularType> for String {
         match val {
             MolecularType::Protein => "protein".to_string(),
             MolecularType::Dna => "dna".to_string(),
+            MolecularType::Rna => "rna".to_string(),
+            MolecularType::Carbohydrate => "carbohydrate".to_string(),
+            MolecularType::Ligand => "ligand".to_string(),
+            MolecularType::Ion => "ion".to_string(),
+            MolecularType::Water => "water".to_string(),
             MolecularType::Other => "other".to_string(),
         }
     }
 }
 
+/// The molecular type of a single residue, as returned by [`identify_residue_types`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ResidueAnnotation {
+    /// The ID of the chain the residue belongs to.
+    pub chain: String,
+    /// The residue serial number.
+    pub serial: isize,
+    /// The residue insertion code, if any.
+    pub insertion_code: Option<String>,
+    /// The residue name.
+    pub name: String,
+    /// The molecular type of the residue.
+    pub mol_type: MolecularType,
+}
+
+/// A source of residue classification, such as a user-extensible [`ResidueDictionary`] or a
+/// [`ComponentRegistry`] loaded from the wwPDB Chemical Component Dictionary.
+pub trait ResidueLookup {
+    /// Returns the `MolecularType` of the residue with the given (uppercase) name, or `None`
+    /// if the residue is not known.
+    fn molecular_type(&self, res_name: &str) -> Option<MolecularType>;
+}
+
+/// The built-in residue tables from the `constants` module.
+struct StandardResidues;
+
+impl ResidueLookup for StandardResidues {
+    fn molecular_type(&self, res_name: &str) -> Option<MolecularType> {
+        classify_residue_name(res_name)
+    }
+}
+
 /// Identifies molecular types in the given PDB structure.
 ///
 /// This function analyzes the chains and residues in a PDB structure to categorize each residue
-/// into molecular types such as Protein, DNA, or Other. It returns a `HashMap` where the keys
-/// are chain IDs and the values are vectors of unique `MolecularType`s present in each chain.
+/// into molecular types such as Protein, DNA, RNA, Carbohydrate, Ion, Water, Ligand or Other.
+/// It returns a `HashMap` where the keys are chain IDs and the values are vectors of unique
+/// `MolecularType`s present in each chain.
+///
+/// Modified residues (e.g. `MSE` or `SEP`) are classified with the molecular type of their
+/// standard parent residue. Residues that are not found in any of the known residue tables are
+/// classified as `Ligand` when they are hetero residues (`HETATM` records) and as `Other` otherwise.
 ///
 /// # Arguments
 ///
@@ -53,25 +139,59 @@ impl From<MolecularType> for String {
 /// }
 /// ```
 ///
-/// # Panics
-///
-/// This function will panic if the residue name cannot be retrieved (`res.name().unwrap()`).
+/// # Notes
 ///
+/// Residues without a name cannot be classified and are skipped; use
+/// [`identify_nameless_residues`] to report them.
 pub fn identify_molecular_types(structure: &pdbtbx::PDB) -> HashMap<String, Vec<MolecularType>> {
+    identify_molecular_types_with(structure, &StandardResidues)
+}
+
+/// Identifies molecular types in the given PDB structure using a custom residue lookup.
+///
+/// This function behaves like [`identify_molecular_types`], but classifies residues with the
+/// given `ResidueLookup` (e.g. a [`ResidueDictionary`] or a [`ComponentRegistry`]) instead of the
+/// built-in residue tables.
+///
+/// # Arguments
+///
+/// * `structure` - A reference to a `pdbtbx::PDB` structure representing the PDB file to be analyzed.
+/// * `lookup` - The `ResidueLookup` used to classify the residues.
+///
+/// # Returns
+///
+/// A `HashMap<String, Vec<MolecularType>>` where each key is a chain ID and each value is a vector
+/// of unique `MolecularType`s found in that chain.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::{identify_molecular_types_with, ComponentRegistry};
+///
+/// let registry = ComponentRegistry::from_file("test_data/components.cif").unwrap();
+/// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+/// let mol_types = identify_molecular_types_with(&pdb, &registry);
+///
+/// for (chain_id, types) in mol_types {
+///     println!("Chain {}: {:?}", chain_id, types);
+/// }
+/// ```
+///
+/// # Notes
+///
+/// Residues without a name cannot be classified and are skipped; use
+/// [`identify_nameless_residues`] to report them.
+pub fn identify_molecular_types_with<L: ResidueLookup + ?Sized>(
+    structure: &pdbtbx::PDB,
+    lookup: &L,
+) -> HashMap<String, Vec<MolecularType>> {
     let mut mol_types = HashMap::new();
 
     for chain in structure.chains() {
         let chain_id = chain.id().to_string();
-        let chain_mol_types = chain.residues().map(|res| {
-            let res_name = res.name().unwrap().to_uppercase();
-            if AMINOACIDS.contains(&res_name.as_str()) {
-                MolecularType::Protein
-            } else if DNA.contains(&res_name.as_str()) {
-                MolecularType::Dna
-            } else {
-                MolecularType::Other
-            }
-        });
+        let chain_mol_types = chain
+            .residues()
+            .filter_map(|res| classify_residue(res, lookup));
 
         let mut unique_mol_types = HashSet::new();
         for mol_type in chain_mol_types {
@@ -87,6 +207,123 @@ pub fn identify_molecular_types(structure: &pdbtbx::PDB) -> HashMap<String, Vec<
     mol_types
 }
 
+/// Identifies the molecular type of every residue in the given PDB structure.
+///
+/// Unlike [`identify_molecular_types`], which collapses each chain into a set of types, this
+/// function returns one entry per residue, in the order in which the residues appear in the
+/// structure. This allows, for instance, to split a mixed chain into a polymer and its ligands.
+///
+/// # Arguments
+///
+/// * `structure` - A reference to a `pdbtbx::PDB` structure representing the PDB file to be analyzed.
+///
+/// # Returns
+///
+/// A `Vec<ResidueAnnotation>` with the chain ID, residue number, insertion code, name and
+/// `MolecularType` of each residue.
+///
+/// # Example
+///
+/// ```rust
+/// use pdbtbx::PDB;
+/// use pdb_handler::{identify_residue_types, MolecularType};
+///
+/// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+/// let residues = identify_residue_types(&pdb);
+///
+/// for res in residues.iter().filter(|res| res.mol_type == MolecularType::Ligand) {
+///     println!("Ligand {} {}{}", res.chain, res.name, res.serial);
+/// }
+/// ```
+///
+/// # Notes
+///
+/// Residues without a name cannot be classified and are skipped; use
+/// [`identify_nameless_residues`] to report them.
+pub fn identify_residue_types(structure: &pdbtbx::PDB) -> Vec<ResidueAnnotation> {
+    identify_residue_types_with(structure, &StandardResidues)
+}
+
+/// Identifies the molecular type of every residue in the given PDB structure using a custom
+/// residue lookup.
+///
+/// This function behaves like [`identify_residue_types`], but classifies residues with the
+/// given `ResidueLookup` (e.g. a [`ResidueDictionary`] or a [`ComponentRegistry`]).
+///
+/// # Notes
+///
+/// Residues without a name cannot be classified and are skipped; use
+/// [`identify_nameless_residues`] to report them.
+pub fn identify_residue_types_with<L: ResidueLookup + ?Sized>(
+    structure: &pdbtbx::PDB,
+    lookup: &L,
+) -> Vec<ResidueAnnotation> {
+    structure
+        .chains()
+        .flat_map(|chain| {
+            chain.residues().filter_map(move |res| {
+                Some(ResidueAnnotation {
+                    chain: chain.id().to_string(),
+                    serial: res.serial_number(),
+                    insertion_code: res.insertion_code().map(String::from),
+                    name: res.name()?.to_string(),
+                    mol_type: classify_residue(res, lookup)?,
+                })
+            })
+        })
+        .collect()
+}
+
+/// Returns the standard parent residue of a modified residue, if it is a known modified residue.
+fn modified_parent(res_name: &str) -> Option<&'static str> {
+    MODIFIED_RESIDUES
+        .iter()
+        .find(|(modified, _)| *modified == res_name)
+        .map(|(_, parent)| *parent)
+}
+
+/// Classifies a residue name into a `MolecularType` using the known residue tables.
+///
+/// Modified residues are classified as their standard parent residue. Returns `None` if the
+/// residue name is not found in any of the tables.
+fn classify_residue_name(res_name: &str) -> Option<MolecularType> {
+    let res_name = modified_parent(res_name).unwrap_or(res_name);
+    if AMINOACIDS.contains(&res_name) {
+        Some(MolecularType::Protein)
+    } else if DNA.contains(&res_name) {
+        Some(MolecularType::Dna)
+    } else if RNA.contains(&res_name) {
+        Some(MolecularType::Rna)
+    } else if WATER.contains(&res_name) {
+        Some(MolecularType::Water)
+    } else if IONS.contains(&res_name) {
+        Some(MolecularType::Ion)
+    } else if CARBOHYDRATES.contains(&res_name) {
+        Some(MolecularType::Carbohydrate)
+    } else {
+        None
+    }
+}
+
+/// Classifies a single residue into a `MolecularType` based on its name.
+///
+/// Residues that are not known to the lookup are classified as `Ligand` if any of their atoms
+/// is a hetero atom, and as `Other` otherwise. Returns `None` if the residue has no name.
+fn classify_residue<L: ResidueLookup + ?Sized>(
+    res: &pdbtbx::Residue,
+    lookup: &L,
+) -> Option<MolecularType> {
+    let res_name = res.name()?.to_uppercase();
+    let mol_type = lookup.molecular_type(&res_name).unwrap_or_else(|| {
+        if res.atoms().any(|atom| atom.hetero()) {
+            MolecularType::Ligand
+        } else {
+            MolecularType::Other
+        }
+    });
+    Some(mol_type)
+}
+
 /// Identifies all chain IDs in the given PDB structure.
 ///
 /// This function iterates over all chains in a PDB structure and collects their IDs into a vector of strings.
@@ -148,32 +385,35 @@ pub fn identify_chains(structure: &pdbtbx::PDB) -> Vec<String> {
 /// }
 /// ```
 ///
+/// # Notes
+///
+/// The residue numbers are sorted as strings, so `10` comes before `9`, and insertion codes are
+/// ignored. Use [`identify_residue_ids`] for numeric ordering and insertion codes, and
+/// [`identify_chain_segments`] to find gaps in the numbering and chain breaks.
+///
 /// # Panics
 ///
 /// This function will panic if the residue serial number cannot be retrieved.
 pub fn identify_residue_numbers(structure: &pdbtbx::PDB) -> HashMap<String, Vec<String>> {
-    structure
-        .chains()
-        .map(|chain| {
-            let resnumbers: Vec<String> = chain
-                .residues()
+    residue_id::chain_residues(structure)
+        .into_iter()
+        .map(|(chain, residues)| {
+            let resnumbers: HashSet<String> = residues
+                .iter()
                 .map(|res| res.serial_number().to_string())
-                .collect::<Vec<_>>()
-                .into_iter()
-                .collect::<std::collections::HashSet<_>>()
-                .into_iter()
                 .collect();
             // Sort the residue numbers
             let mut resnumbers = resnumbers.into_iter().collect::<Vec<_>>();
             resnumbers.sort();
-            (chain.id().to_string(), resnumbers)
+            (chain.to_string(), resnumbers)
         })
         .collect()
 }
 
 /// Identifies unknown residues in each chain of the given PDB structure.
 ///
-/// This function iterates over all chains in a PDB structure, filters out known residues (amino acids and DNA),
+/// This function iterates over all chains in a PDB structure, filters out known residues (amino acids,
+/// nucleotides, modified residues, waters, ions and carbohydrates),
 /// and collects the names of unknown residues. It returns a `HashMap` where the keys are chain IDs and the
 /// values are vectors of unique unknown residue names.
 ///
@@ -200,23 +440,59 @@ pub fn identify_residue_numbers(structure: &pdbtbx::PDB) -> HashMap<String, Vec<
 /// }
 /// ```
 ///
-/// # Panics
+/// # Notes
 ///
-/// This function will panic if the residue name cannot be retrieved.
+/// Residues without a name cannot be classified and are skipped; use
+/// [`identify_nameless_residues`] to report them.
 pub fn identify_unknowns(structure: &pdbtbx::PDB) -> HashMap<String, Vec<String>> {
-    let mut res_map = HashMap::new();
+    identify_unknowns_with(structure, &StandardResidues)
+}
 
-    let known_residues: HashSet<_> = AMINOACIDS
-        .iter()
-        .chain(DNA.iter())
-        .map(|s| s.to_uppercase())
-        .collect();
+/// Identifies unknown residues in each chain of the given PDB structure using a custom residue lookup.
+///
+/// This function behaves like [`identify_unknowns`], but a residue is considered unknown when the
+/// given `ResidueLookup` (e.g. a [`ResidueDictionary`] or a [`ComponentRegistry`]) cannot classify it.
+///
+/// # Arguments
+///
+/// * `structure` - A reference to a `pdbtbx::PDB` structure representing the PDB file to be analyzed.
+/// * `lookup` - The `ResidueLookup` used to classify the residues.
+///
+/// # Returns
+///
+/// A `HashMap<String, Vec<String>>` where each key is a chain ID and each value is a vector of unique
+/// unknown residue names found in that chain.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::{identify_unknowns_with, ComponentRegistry};
+///
+/// let registry = ComponentRegistry::from_file("test_data/components.cif").unwrap();
+/// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+/// let unknown_residues = identify_unknowns_with(&pdb, &registry);
+///
+/// for (chain_id, residues) in unknown_residues {
+///    println!("Chain {}: {:?}", chain_id, residues);
+/// }
+/// ```
+///
+/// # Notes
+///
+/// Residues without a name cannot be classified and are skipped; use
+/// [`identify_nameless_residues`] to report them.
+pub fn identify_unknowns_with<L: ResidueLookup + ?Sized>(
+    structure: &pdbtbx::PDB,
+    lookup: &L,
+) -> HashMap<String, Vec<String>> {
+    let mut res_map = HashMap::new();
 
     for chain in structure.chains() {
         let chain_residues: Vec<_> = chain
             .residues()
-            .filter(|res| !known_residues.contains(&res.name().unwrap().to_uppercase()))
-            .map(|res| res.name().unwrap().to_string())
+            .filter_map(|res| res.name())
+            .filter(|res_name| lookup.molecular_type(&res_name.to_uppercase()).is_none())
+            .map(|res_name| res_name.to_string())
             .collect();
 
         let mut chain_residues = chain_residues;
@@ -230,11 +506,11 @@ pub fn identify_unknowns(structure: &pdbtbx::PDB) -> HashMap<String, Vec<String>
     res_map
 }
 
-/// Identifies pairs of chains that are in close contact within the given PDB structure.
+/// Identifies modified residues in each chain of the given PDB structure.
 ///
-/// This function analyzes inter-chain interactions by checking for atoms from different chains
-/// that are within a specified distance threshold (5.0 Å). It returns a list of unique chain pairs
-/// where at least one pair of atoms from each chain is within the contact distance.
+/// This function iterates over all chains in a PDB structure and collects the residues that are
+/// known modifications of a standard amino acid or nucleotide (e.g. `MSE` for `MET` or `5CM` for `DC`),
+/// together with their standard parent residue.
 ///
 /// # Arguments
 ///
@@ -242,79 +518,106 @@ pub fn identify_unknowns(structure: &pdbtbx::PDB) -> HashMap<String, Vec<String>
 ///
 /// # Returns
 ///
-/// A `Vec<(String, String)>` where each tuple represents a pair of chain IDs that are in contact.
-/// The pairs are unordered and unique (e.g., if (A, B) is present, (B, A) will not be included).
+/// A `HashMap<String, Vec<(String, String)>>` where each key is a chain ID and each value is a vector
+/// of unique `(modified, parent)` residue name pairs found in that chain.
 ///
 /// # Example
 ///
 /// ```rust
 /// use pdbtbx::PDB;
-/// use pdb_handler::chains_in_contact;
+/// use pdb_handler::identify_modified_residues;
 ///
 /// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
-/// let contacting_chains = chains_in_contact(&pdb);
+/// let modified_residues = identify_modified_residues(&pdb);
 ///
-/// for (chain_a, chain_b) in contacting_chains {
-///     println!("Chains {} and {} are in contact", chain_a, chain_b);
+/// for (chain_id, residues) in modified_residues {
+///     for (modified, parent) in residues {
+///         println!("Chain {}: {} is a modified {}", chain_id, modified, parent);
+///     }
 /// }
 /// ```
 ///
 /// # Notes
 ///
-/// - The contact distance threshold is fixed at 5.0 Å.
-/// - Self-contacts (within the same chain) are ignored.
-/// - The function uses a HashSet internally to ensure unique pairings.
-pub fn chains_in_contact(structure: &pdbtbx::PDB) -> Vec<(String, String)> {
-    let mut contacts: HashSet<Vec<String>> = HashSet::new();
+/// Residues without a name cannot be classified and are skipped; use
+/// [`identify_nameless_residues`] to report them.
+pub fn identify_modified_residues(
+    structure: &pdbtbx::PDB,
+) -> HashMap<String, Vec<(String, String)>> {
+    let mut res_map = HashMap::new();
 
-    for (chain_x, chain_y) in structure
-        .chains()
-        .flat_map(|cx| structure.chains().map(move |cy| (cx, cy)))
-    {
-        if chain_x.id() == chain_y.id() {
-            continue;
-        }
+    for chain in structure.chains() {
+        let mut chain_residues: Vec<_> = chain
+            .residues()
+            .filter_map(|res| {
+                let res_name = res.name()?.to_uppercase();
+                modified_parent(&res_name).map(|parent| (res_name, parent.to_string()))
+            })
+            .collect();
 
-        let mut in_contacts = false;
-        for contact in &contacts {
-            if contact.contains(&chain_x.id().to_string())
-                && contact.contains(&chain_y.id().to_string())
-            {
-                in_contacts = true;
-                break;
-            }
-        }
+        chain_residues.sort();
+        chain_residues.dedup();
 
-        if in_contacts {
-            continue;
-        }
+        res_map.insert(chain.id().to_string(), chain_residues);
+    }
 
-        for res_x in chain_x.residues() {
-            for res_y in chain_y.residues() {
-                for atom_i in res_x.atoms() {
-                    for atom_j in res_y.atoms() {
-                        let dist = atom_i.distance(atom_j);
-                        if dist <= 5.0 {
-                            contacts
-                                .insert(vec![chain_x.id().to_string(), chain_y.id().to_string()]);
-                        }
-                    }
-                }
-            }
+    res_map
+}
+
+/// Identifies residues without a name in each chain of the given PDB structure.
+///
+/// A residue has no name when it has no conformers, which can happen in loosely parsed or
+/// programmatically built structures. Such residues cannot be classified and are skipped by
+/// the other `identify_*` functions; this function reports them instead.
+///
+/// # Arguments
+///
+/// * `structure` - A reference to a `pdbtbx::PDB` structure representing the PDB file to be analyzed.
+///
+/// # Returns
+///
+/// A `HashMap<String, Vec<String>>` where each key is a chain ID and each value is a vector of the
+/// residue numbers (including insertion codes) of the nameless residues in that chain. Chains
+/// without nameless residues are not included.
+///
+/// # Example
+///
+/// ```rust
+/// use pdbtbx::PDB;
+/// use pdb_handler::identify_nameless_residues;
+///
+/// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+/// let nameless = identify_nameless_residues(&pdb);
+///
+/// for (chain_id, numbers) in nameless {
+///     println!("Chain {}: residues {:?} have no name", chain_id, numbers);
+/// }
+/// ```
+pub fn identify_nameless_residues(structure: &pdbtbx::PDB) -> HashMap<String, Vec<String>> {
+    let mut res_map: HashMap<String, Vec<String>> = HashMap::new();
+
+    for chain in structure.chains() {
+        for res in chain.residues().filter(|res| res.name().is_none()) {
+            let number = format!(
+                "{}{}",
+                res.serial_number(),
+                res.insertion_code().unwrap_or("")
+            );
+            res_map
+                .entry(chain.id().to_string())
+                .or_default()
+                .push(number);
         }
     }
 
-    contacts
-        .into_iter()
-        .map(|pair| (pair[0].clone(), pair[1].clone()))
-        .collect()
+    res_map
 }
 
 /// Removes lines starting with "REMARK" from a PDB file and returns the filtered content as a BufReader.
 ///
-/// This function reads a Protein Data Bank (PDB) file, filters out all lines that start with the keyword "REMARK",
-/// and returns the remaining content as a `BufReader` over an in-memory buffer. This allows for further processing
-/// of the filtered content without needing to write it to a temporary file.
+/// This function opens a Protein Data Bank (PDB) file and returns a `BufReader` that lazily filters out all
+/// lines that start with the keyword "REMARK". The file is processed one line at a time, so memory usage does
+/// not depend on its size, and the reader can be passed directly to `pdbtbx::ReadOptions::read_raw`.
 ///
 /// # Arguments
 ///
@@ -322,106 +625,299 @@ pub fn chains_in_contact(structure: &pdbtbx::PDB) -> Vec<(String, String)> {
 ///
 /// # Returns
 ///
-/// * `BufReader<Cursor<Vec<u8>>>` - A `BufReader` containing the filtered content.
+/// * `Result<BufReader<PdbLineReader<BufReader<File>>>, PdbHandlerError>` - A `BufReader` over the filtered
+///   content, or an error if the file cannot be opened. Read and encoding errors are reported when reading.
+///
+/// # Examples
+///
+/// ```
+/// use pdb_handler::try_remove_remark;
+/// use std::io::BufRead;
+/// let reader = try_remove_remark("example-pdbs/1crn.pdb").unwrap();
+/// for line in reader.lines() {
+///     println!("{:?}", line.unwrap());
+/// }
+/// ```
+pub fn try_remove_remark(
+    pdb_f: &str,
+) -> Result<BufReader<PdbLineReader<BufReader<File>>>, PdbHandlerError> {
+    // Open the input file
+    let input_file = File::open(pdb_f)?;
+    Ok(remove_remark_from_reader(BufReader::new(input_file)))
+}
+
+/// Removes lines starting with "REMARK" from any buffered reader and returns the filtered content
+/// as a BufReader.
+///
+/// This is the reader-based version of [`try_remove_remark`], for content that does not come from
+/// a file, such as upload bodies, decompressed streams or in-memory buffers.
+///
+/// # Arguments
+///
+/// * `reader` - A `BufRead` over the PDB content.
+///
+/// # Returns
+///
+/// A `BufReader` that lazily filters the content. Read and encoding errors are reported when reading.
+///
+/// # Examples
+///
+/// ```
+/// use pdb_handler::remove_remark_from_reader;
+/// use std::io::BufRead;
+///
+/// let content = "REMARK 290\nATOM      1  N   GLY A  42\n";
+/// let reader = remove_remark_from_reader(content.as_bytes());
+/// assert_eq!(reader.lines().count(), 1);
+/// ```
+pub fn remove_remark_from_reader<R: BufRead>(reader: R) -> BufReader<PdbLineReader<R>> {
+    PdbLineFilter::new(reader).remove_remarks().into_reader()
+}
+
+/// Removes lines starting with "REMARK" from any buffered reader and writes the remaining lines
+/// to `writer`.
+///
+/// # Arguments
+///
+/// * `reader` - A `BufRead` over the PDB content.
+/// * `writer` - The destination of the filtered content.
+///
+/// # Returns
+///
+/// `Ok(())` on success, or an error if the content cannot be read, is not valid UTF-8 or cannot
+/// be written.
+///
+/// # Examples
+///
+/// ```
+/// use pdb_handler::remove_remark_to_writer;
+/// use std::fs::File;
+/// use std::io::BufReader;
+///
+/// let input = BufReader::new(File::open("example-pdbs/1crn.pdb").unwrap());
+/// let mut output = Vec::new();
+/// remove_remark_to_writer(input, &mut output).unwrap();
+/// ```
+pub fn remove_remark_to_writer<R: BufRead, W: Write>(
+    reader: R,
+    writer: W,
+) -> Result<(), PdbHandlerError> {
+    PdbLineFilter::new(reader).remove_remarks().write_to(writer)
+}
+
+/// Removes lines starting with "REMARK" from a PDB file and returns the filtered content as a BufReader.
 ///
 /// # Panics
 ///
 /// This function will panic if the input file cannot be opened or read.
+#[deprecated(since = "0.3.0", note = "use `try_remove_remark` instead")]
+pub fn remove_remark(pdb_f: &str) -> BufReader<Cursor<Vec<u8>>> {
+    let mut filtered_content = Vec::new();
+    try_remove_remark(pdb_f)
+        .unwrap()
+        .read_to_end(&mut filtered_content)
+        .unwrap();
+    BufReader::new(Cursor::new(filtered_content))
+}
+
+/// Filters the records of a PDB file and returns the filtered content as a BufReader.
+///
+/// This generalises [`try_remove_remark`] to any PDB record type: the `filter` either keeps only
+/// the listed records (e.g. `ATOM`, `TER` and `END`) or drops them (e.g. `ANISOU`, `CONECT` or
+/// `REMARK 350`), see [`RecordFilter`]. The file is processed one line at a time.
+///
+/// # Arguments
+///
+/// * `pdb_f` - A string slice that holds the path to the input PDB file.
+/// * `filter` - The records to keep or drop.
+///
+/// # Returns
+///
+/// A `BufReader` over the filtered content, or an error if the file cannot be opened. Read and
+/// encoding errors are reported when reading.
 ///
 /// # Examples
 ///
 /// ```
-/// use pdb_handler::remove_remark;
+/// use pdb_handler::{filter_records, RecordFilter};
 /// use std::io::BufRead;
-/// let reader = remove_remark("example-pdbs/1crn.pdb");
+///
+/// let filter = RecordFilter::keep(["ATOM", "TER", "END"]);
+/// let reader = filter_records("example-pdbs/1crn.pdb", &filter).unwrap();
 /// for line in reader.lines() {
 ///     println!("{:?}", line.unwrap());
 /// }
 /// ```
-pub fn remove_remark(pdb_f: &str) -> BufReader<Cursor<Vec<u8>>> {
+pub fn filter_records(
+    pdb_f: &str,
+    filter: &RecordFilter,
+) -> Result<BufReader<PdbLineReader<BufReader<File>>>, PdbHandlerError> {
     // Open the input file
-    let input_file = File::open(pdb_f).unwrap();
-    let reader = BufReader::new(input_file);
-
-    // Collect filtered lines into a vector
-    let filtered_content: Vec<u8> = reader
-        .lines()
-        .filter_map(|line| {
-            let line = line.unwrap();
-            if !line.starts_with("REMARK") {
-                Some(line + "\n")
-            } else {
-                None
-            }
-        })
-        .collect::<String>()
-        .into_bytes();
+    let input_file = File::open(pdb_f)?;
+    Ok(filter_records_from_reader(
+        BufReader::new(input_file),
+        filter,
+    ))
+}
 
-    // Create a BufReader over an in-memory buffer
-    BufReader::new(Cursor::new(filtered_content))
+/// Filters the records of any buffered reader and returns the filtered content as a BufReader.
+///
+/// This is the reader-based version of [`filter_records`].
+///
+/// # Arguments
+///
+/// * `reader` - A `BufRead` over the PDB content.
+/// * `filter` - The records to keep or drop.
+///
+/// # Returns
+///
+/// A `BufReader` that lazily filters the content. Read and encoding errors are reported when reading.
+pub fn filter_records_from_reader<R: BufRead>(
+    reader: R,
+    filter: &RecordFilter,
+) -> BufReader<PdbLineReader<R>> {
+    PdbLineFilter::new(reader)
+        .filter_records(filter.clone())
+        .into_reader()
 }
 
-/// Reads a text file specified by `pdb_f`, pads each line that starts with `ATOM` to 80 characters
-/// with spaces, and returns a buffered reader over an in-memory buffer
-/// containing the padded content.
+/// Filters the records of any buffered reader and writes the remaining lines to `writer`.
 ///
 /// # Arguments
 ///
-/// * `pdb_f` - A string slice that holds the path to the input text file.
+/// * `reader` - A `BufRead` over the PDB content.
+/// * `writer` - The destination of the filtered content.
+/// * `filter` - The records to keep or drop.
 ///
 /// # Returns
 ///
-/// A `BufReader` wrapped around a `Cursor<Vec<u8>>>`, where each line from
-/// the input file is padded to 80 characters with spaces and newline character.
+/// `Ok(())` on success, or an error if the content cannot be read, is not valid UTF-8 or cannot
+/// be written.
 ///
-/// # Panics
+/// # Examples
 ///
-/// This function panics if it encounters any I/O errors while reading or
-/// processing the file.
+/// ```
+/// use pdb_handler::{filter_records_to_writer, RecordFilter};
+///
+/// let content = "REMARK 350 BIOMOLECULE: 1\nATOM      1  N   GLY A  42\nCONECT    1    2\n";
+/// let mut output = Vec::new();
+/// let filter = RecordFilter::drop(["REMARK 350", "CONECT"]);
+/// filter_records_to_writer(content.as_bytes(), &mut output, &filter).unwrap();
+/// assert_eq!(String::from_utf8(output).unwrap(), "ATOM      1  N   GLY A  42\n");
+/// ```
+pub fn filter_records_to_writer<R: BufRead, W: Write>(
+    reader: R,
+    writer: W,
+    filter: &RecordFilter,
+) -> Result<(), PdbHandlerError> {
+    PdbLineFilter::new(reader)
+        .filter_records(filter.clone())
+        .write_to(writer)
+}
+
+/// Opens a text file specified by `pdb_f` and returns a buffered reader that pads each coordinate
+/// record (`ATOM`, `HETATM`, `ANISOU` and `TER`) to 80 characters with spaces.
+///
+/// The file is processed one line at a time, so memory usage does not depend on its size, and the
+/// reader can be passed directly to `pdbtbx::ReadOptions::read_raw`. To pad a different set of
+/// records, use [`PdbLineFilter::pad_records`].
+///
+/// # Arguments
+///
+/// * `pdb_f` - A string slice that holds the path to the input text file.
+///
+/// # Returns
+///
+/// A `BufReader` over the content of the file, where each coordinate record is padded to 80 characters
+/// with spaces and every line ends with a newline character, or an error if the file cannot be
+/// opened. Read and encoding errors are reported when reading.
 ///
 /// # Examples
 ///
 /// ```rust
-/// use pdb_handler::pad_lines;
+/// use pdb_handler::try_pad_lines;
 /// use std::io::Read;
 /// use std::io::BufReader;
 ///
-/// let mut padded_reader = pad_lines("example-pdbs/dna.pdb");
+/// let mut padded_reader = try_pad_lines("example-pdbs/dna.pdb").unwrap();
 /// let mut buffer = String::new();
 /// padded_reader.read_to_string(&mut buffer).unwrap();
 /// println!("Padded content:\n{}", buffer);
 /// ```
 ///
-/// This example reads lines from "dna.pdb", pads each line that starts with `ATOM` with spaces
+/// This example reads lines from "dna.pdb", pads each coordinate record with spaces
 /// to reach 80 characters, and then prints out the padded content.
-pub fn pad_lines(pdb_f: &str) -> BufReader<Cursor<Vec<u8>>> {
+pub fn try_pad_lines(
+    pdb_f: &str,
+) -> Result<BufReader<PdbLineReader<BufReader<File>>>, PdbHandlerError> {
     // Open the input file
-    let input_file = File::open(pdb_f).unwrap();
-    let reader = BufReader::new(input_file);
-
-    // Collect filtered lines into a vector
-    let filtered_content: Vec<u8> = reader
-        .lines()
-        .flat_map(|line| {
-            let line = line.unwrap();
-            let mut processed_line = if line.starts_with("ATOM") {
-                let mut padded_line = line.to_string();
-                if line.len() <= 80 {
-                    padded_line.push_str(" ".repeat(80 - line.len()).as_str());
-                    padded_line
-                } else {
-                    line[..80].to_string()
-                }
-            } else {
-                line
-            };
-            processed_line.push('\n'); // Append newline
-            processed_line.into_bytes()
-        })
-        .collect();
+    let input_file = File::open(pdb_f)?;
+    Ok(pad_lines_from_reader(BufReader::new(input_file)))
+}
 
-    // Create a BufReader over an in-memory buffer
-    BufReader::new(Cursor::new(filtered_content))
+/// Pads each coordinate record to 80 characters from any buffered reader and returns
+/// the padded content as a BufReader.
+///
+/// This is the reader-based version of [`try_pad_lines`], for content that does not come from
+/// a file, such as upload bodies, decompressed streams or in-memory buffers.
+///
+/// # Arguments
+///
+/// * `reader` - A `BufRead` over the PDB content.
+///
+/// # Returns
+///
+/// A `BufReader` that lazily pads the content. Read and encoding errors are reported when reading.
+///
+/// # Examples
+///
+/// ```
+/// use pdb_handler::pad_lines_from_reader;
+/// use std::io::BufRead;
+///
+/// let content = "ATOM      1  N   GLY A  42\n";
+/// let reader = pad_lines_from_reader(content.as_bytes());
+/// assert!(reader.lines().all(|line| line.unwrap().len() == 80));
+/// ```
+pub fn pad_lines_from_reader<R: BufRead>(reader: R) -> BufReader<PdbLineReader<R>> {
+    PdbLineFilter::new(reader).pad().into_reader()
+}
+
+/// Pads each coordinate record to 80 characters from any buffered reader and writes
+/// all lines to `writer`.
+///
+/// # Arguments
+///
+/// * `reader` - A `BufRead` over the PDB content.
+/// * `writer` - The destination of the padded content.
+///
+/// # Returns
+///
+/// `Ok(())` on success, or an error if the content cannot be read, is not valid UTF-8 or cannot
+/// be written.
+pub fn pad_lines_to_writer<R: BufRead, W: Write>(
+    reader: R,
+    writer: W,
+) -> Result<(), PdbHandlerError> {
+    PdbLineFilter::new(reader).pad().write_to(writer)
+}
+
+/// Reads a text file specified by `pdb_f`, pads each coordinate record to 80 characters
+/// with spaces, and returns a buffered reader over an in-memory buffer
+/// containing the padded content.
+///
+/// # Panics
+///
+/// This function panics if it encounters any I/O errors while reading or
+/// processing the file.
+#[deprecated(since = "0.3.0", note = "use `try_pad_lines` instead")]
+pub fn pad_lines(pdb_f: &str) -> BufReader<Cursor<Vec<u8>>> {
+    let mut padded_content = Vec::new();
+    try_pad_lines(pdb_f)
+        .unwrap()
+        .read_to_end(&mut padded_content)
+        .unwrap();
+    BufReader::new(Cursor::new(padded_content))
 }
 
 #[cfg(test)]
@@ -446,12 +942,84 @@ mod tests {
         let mut expected = HashMap::new();
         expected.insert(
             "A".to_string(),
-            vec![MolecularType::Protein, MolecularType::Other],
+            vec![MolecularType::Protein, MolecularType::Ligand],
         );
 
         assert_eq!(mol_types, expected);
     }
 
+    #[test]
+    fn test_identify_molecular_types_hetero() {
+        // Load the structure from the test_data folder
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/hetero_mix.pdb")
+            .unwrap();
+
+        let mol_types = identify_molecular_types(&structure);
+
+        let mut expected = HashMap::new();
+        expected.insert(
+            "A".to_string(),
+            vec![
+                MolecularType::Protein,
+                MolecularType::Carbohydrate,
+                MolecularType::Ligand,
+                MolecularType::Ion,
+                MolecularType::Water,
+            ],
+        );
+
+        assert_eq!(mol_types, expected);
+    }
+
+    #[test]
+    fn test_identify_molecular_types_rna() {
+        // Load the structure from the test_data folder
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .set_level(pdbtbx::StrictnessLevel::Loose)
+            .read("test_data/pdb_w_long_lines.pdb")
+            .unwrap();
+
+        let mol_types = identify_molecular_types(&structure);
+
+        let mut expected = HashMap::new();
+        expected.insert("B".to_string(), vec![MolecularType::Rna]);
+
+        assert_eq!(mol_types, expected);
+    }
+
+    #[test]
+    fn test_identify_residue_types() {
+        // Load the structure from the test_data folder
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/hetero_mix.pdb")
+            .unwrap();
+
+        let residues = identify_residue_types(&structure);
+
+        let observed: Vec<_> = residues
+            .iter()
+            .map(|res| (res.serial, res.name.as_str(), res.mol_type.clone()))
+            .collect();
+
+        let expected = vec![
+            (1, "ALA", MolecularType::Protein),
+            (101, "NAG", MolecularType::Carbohydrate),
+            (102, "ZN", MolecularType::Ion),
+            (103, "I09", MolecularType::Ligand),
+            (201, "HOH", MolecularType::Water),
+            (202, "HOH", MolecularType::Water),
+        ];
+
+        assert_eq!(observed, expected);
+        assert!(residues
+            .iter()
+            .all(|res| res.chain == "A" && res.insertion_code.is_none()));
+    }
+
     #[test]
     fn test_identify_chains() {
         // Load the structure from the test_data folder
@@ -501,24 +1069,64 @@ mod tests {
     }
 
     #[test]
-    fn test_chains_in_contact() {
+    fn test_identify_unknowns_hetero() {
+        // Load the structure from the test_data folder
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/hetero_mix.pdb")
+            .unwrap();
+
+        let unknowns = identify_unknowns(&structure);
+
+        let mut expected = HashMap::new();
+        expected.insert("A".to_string(), vec!["I09".to_string()]);
+
+        assert_eq!(unknowns, expected);
+    }
+
+    #[test]
+    fn test_identify_modified_residues() {
         // Load the structure from the test_data folder
         let (structure, _) = ReadOptions::default()
             .set_format(pdbtbx::Format::Pdb)
-            .read("test_data/chains_in_contact.pdb")
+            .read("test_data/modified_residues.pdb")
             .unwrap();
 
-        let contacts = chains_in_contact(&structure);
+        let modified = identify_modified_residues(&structure);
+
+        let mut expected = HashMap::new();
+        expected.insert(
+            "A".to_string(),
+            vec![
+                ("MSE".to_string(), "MET".to_string()),
+                ("SEP".to_string(), "SER".to_string()),
+            ],
+        );
+        expected.insert("B".to_string(), vec![("5CM".to_string(), "DC".to_string())]);
+
+        assert_eq!(modified, expected);
+    }
+
+    #[test]
+    fn test_modified_residues_are_polymer() {
+        // Load the structure from the test_data folder
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/modified_residues.pdb")
+            .unwrap();
 
-        let expected = vec![("A".to_string(), "B".to_string())];
+        let mol_types = identify_molecular_types(&structure);
+        let unknowns = identify_unknowns(&structure);
 
-        assert_eq!(contacts, expected);
+        assert_eq!(mol_types["A"], vec![MolecularType::Protein]);
+        assert_eq!(mol_types["B"], vec![MolecularType::Dna]);
+        assert!(unknowns.values().all(|residues| residues.is_empty()));
     }
 
     #[test]
     fn test_remove_remarks() {
         let input_pdb = "test_data/pdb_w_remark.pdb";
-        let reader = remove_remark(input_pdb);
+        let reader = try_remove_remark(input_pdb).unwrap();
 
         // Collect the lines from the reader and check if the REMARK lines are removed
         let lines: Vec<String> = reader.lines().map(|line| line.unwrap()).collect();
@@ -526,11 +1134,35 @@ mod tests {
         assert!(!lines.iter().any(|line| line.starts_with("REMARK")));
     }
 
+    #[test]
+    fn test_filter_records() {
+        let filter = RecordFilter::keep(["ATOM", "TER", "END"]);
+        let lines: Vec<String> = filter_records("test_data/prot_ligand.pdb", &filter)
+            .unwrap()
+            .lines()
+            .map(|line| line.unwrap())
+            .collect();
+
+        assert!(!lines.is_empty());
+        assert!(lines
+            .iter()
+            .all(|line| line.starts_with("ATOM") || line.starts_with("TER") || line == "END"));
+
+        let filter = RecordFilter::drop(["REMARK 350", "HETATM"]);
+        let content = "REMARK 350 BIOMOLECULE: 1\nREMARK 290\nATOM      1  N   GLY A  42\nHETATM    2  O   HOH A 101\n";
+        let lines: Vec<String> = filter_records_from_reader(content.as_bytes(), &filter)
+            .lines()
+            .map(|line| line.unwrap())
+            .collect();
+
+        assert_eq!(lines, vec!["REMARK 290", "ATOM      1  N   GLY A  42"]);
+    }
+
     #[test]
     fn test_pad_short_lines() {
         let input_pdb = "test_data/pdb_w_short_lines.pdb";
 
-        let reader = pad_lines(input_pdb);
+        let reader = try_pad_lines(input_pdb).unwrap();
 
         let lines: Vec<String> = reader.lines().map(|line| line.unwrap()).collect();
 
@@ -543,7 +1175,7 @@ mod tests {
     fn test_pad_long_lines() {
         let input_pdb = "test_data/pdb_w_long_lines.pdb";
 
-        let reader = pad_lines(input_pdb);
+        let reader = try_pad_lines(input_pdb).unwrap();
 
         let lines: Vec<String> = reader.lines().map(|line| line.unwrap()).collect();
 
@@ -552,4 +1184,117 @@ mod tests {
             .filter(|line| line.starts_with("ATOM"))
             .all(|line| line.len() == 80));
     }
+
+    #[test]
+    fn test_pad_hetatm_lines() {
+        let lines: Vec<String> = try_pad_lines("test_data/prot_ligand.pdb")
+            .unwrap()
+            .lines()
+            .map(|line| line.unwrap())
+            .collect();
+
+        assert!(lines
+            .iter()
+            .filter(|line| line.starts_with("ATOM") || line.starts_with("HETATM"))
+            .all(|line| line.len() == 80));
+        assert_eq!(lines.last().unwrap(), "END");
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let result = try_pad_lines("test_data/does_not_exist.pdb");
+
+        assert!(matches!(result, Err(PdbHandlerError::Io(_))));
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let input_pdb = "test_data/pdb_w_invalid_utf8.pdb";
+
+        let mut buffer = String::new();
+        let error = try_remove_remark(input_pdb)
+            .unwrap()
+            .read_to_string(&mut buffer)
+            .unwrap_err();
+        assert!(matches!(
+            error.get_ref().unwrap().downcast_ref::<PdbHandlerError>(),
+            Some(PdbHandlerError::Encoding { line: 2 })
+        ));
+
+        let mut output = Vec::new();
+        let result = pad_lines_to_writer(
+            File::open(input_pdb).map(BufReader::new).unwrap(),
+            &mut output,
+        );
+        assert!(matches!(result, Err(PdbHandlerError::Encoding { line: 2 })));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_wrappers() {
+        let lines: Vec<String> = remove_remark("test_data/pdb_w_remark.pdb")
+            .lines()
+            .map(|line| line.unwrap())
+            .collect();
+        assert!(!lines.iter().any(|line| line.starts_with("REMARK")));
+
+        let lines: Vec<String> = pad_lines("test_data/pdb_w_short_lines.pdb")
+            .lines()
+            .map(|line| line.unwrap())
+            .collect();
+        assert!(lines
+            .iter()
+            .filter(|line| line.starts_with("ATOM"))
+            .all(|line| line.len() == 80));
+    }
+
+    #[test]
+    fn test_nameless_residues() {
+        // Load the structure from the test_data folder
+        let (mut structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/prot_ligand.pdb")
+            .unwrap();
+
+        // A residue without conformers has no name
+        structure
+            .chains_mut()
+            .next()
+            .unwrap()
+            .add_residue(pdbtbx::Residue::new(300, Some("A"), None).unwrap());
+
+        let nameless = identify_nameless_residues(&structure);
+        let mut expected = HashMap::new();
+        expected.insert("A".to_string(), vec!["300A".to_string()]);
+        assert_eq!(nameless, expected);
+
+        assert_eq!(
+            identify_molecular_types(&structure)["A"],
+            vec![MolecularType::Protein, MolecularType::Ligand]
+        );
+        assert_eq!(identify_unknowns(&structure)["A"], vec!["I09".to_string()]);
+        assert!(identify_modified_residues(&structure)["A"].is_empty());
+        assert_eq!(identify_residue_types(&structure).len(), 2);
+    }
+
+    #[test]
+    fn test_reader_functions() {
+        let content = std::fs::read("test_data/pdb_w_remark.pdb").unwrap();
+
+        let lines: Vec<String> = remove_remark_from_reader(content.as_slice())
+            .lines()
+            .map(|line| line.unwrap())
+            .collect();
+        assert_eq!(lines.len(), 4);
+        assert!(!lines.iter().any(|line| line.starts_with("REMARK")));
+
+        let mut output = Vec::new();
+        pad_lines_to_writer(content.as_slice(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output
+            .lines()
+            .filter(|line| line.starts_with("ATOM"))
+            .all(|line| line.len() == 80));
+        assert!(output.starts_with("REMARK 290\n"));
+    }
 }
@@ -0,0 +1,375 @@
+use crate::elements::fill_missing_element;
+use crate::{PdbHandlerError, RecordFilter};
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+/// The coordinate-style records that are padded to 80 characters by [`PdbLineFilter::pad`].
+pub const COORDINATE_RECORDS: [&str; 4] = ["ATOM", "HETATM", "ANISOU", "TER"];
+
+type LineTransform = Box<dyn FnMut(String) -> Option<String>>;
+
+/// A lazy, composable pipeline of line transformations over PDB content.
+///
+/// The filter reads one line at a time from the underlying reader and passes it through each
+/// transformation in the order in which they were added, so memory usage does not depend on the
+/// size of the input. Lines are yielded without their line ending; invalid UTF-8 is reported as
+/// `PdbHandlerError::Encoding` with the offending line number.
+///
+/// The filter can be consumed as an iterator of lines, written to any `Write` with
+/// [`PdbLineFilter::write_to`], or turned into a `BufReader` with [`PdbLineFilter::into_reader`],
+/// which can be passed directly to `pdbtbx::ReadOptions::read_raw`.
+///
+/// # Examples
+///
+/// ```
+/// use pdb_handler::PdbLineFilter;
+/// use std::fs::File;
+/// use std::io::BufReader;
+///
+/// let file = File::open("example-pdbs/1crn.pdb").unwrap();
+/// let reader = PdbLineFilter::new(BufReader::new(file))
+///     .remove_remarks()
+///     .pad()
+///     .into_reader();
+///
+/// let (pdb, _errors) = pdbtbx::ReadOptions::default()
+///     .set_format(pdbtbx::Format::Pdb)
+///     .read_raw(reader)
+///     .unwrap();
+/// ```
+pub struct PdbLineFilter<R> {
+    reader: R,
+    line_number: usize,
+    finished: bool,
+    transforms: Vec<LineTransform>,
+}
+
+impl<R: BufRead> PdbLineFilter<R> {
+    /// Creates a new filter over `reader` without any transformation.
+    pub fn new(reader: R) -> Self {
+        PdbLineFilter {
+            reader,
+            line_number: 0,
+            finished: false,
+            transforms: Vec::new(),
+        }
+    }
+
+    /// Adds a transformation that can modify a line, or drop it by returning `None`.
+    pub fn filter_map_lines<F>(mut self, transform: F) -> Self
+    where
+        F: FnMut(String) -> Option<String> + 'static,
+    {
+        self.transforms.push(Box::new(transform));
+        self
+    }
+
+    /// Adds a transformation that keeps only the lines for which `predicate` returns true.
+    pub fn filter_lines<F>(self, mut predicate: F) -> Self
+    where
+        F: FnMut(&str) -> bool + 'static,
+    {
+        self.filter_map_lines(move |line| if predicate(&line) { Some(line) } else { None })
+    }
+
+    /// Adds a transformation that modifies every line.
+    pub fn map_lines<F>(self, mut transform: F) -> Self
+    where
+        F: FnMut(String) -> String + 'static,
+    {
+        self.filter_map_lines(move |line| Some(transform(line)))
+    }
+
+    /// Keeps only the lines that pass the given [`RecordFilter`].
+    pub fn filter_records(self, filter: RecordFilter) -> Self {
+        self.filter_lines(move |line| filter.is_kept(line))
+    }
+
+    /// Drops all `REMARK` lines.
+    pub fn remove_remarks(self) -> Self {
+        self.filter_records(RecordFilter::drop(["REMARK"]))
+    }
+
+    /// Pads each coordinate record (see [`COORDINATE_RECORDS`]) with spaces, or truncates it, to
+    /// 80 characters.
+    ///
+    /// Unlike [`PdbLineFilter::pad_records`], lines are matched by prefix, so records whose atom
+    /// serial runs into the record name, e.g. `ATOM 100000` in large assemblies, are padded too.
+    pub fn pad(self) -> Self {
+        self.map_lines(|line| {
+            if COORDINATE_RECORDS
+                .iter()
+                .any(|record| line.starts_with(record))
+            {
+                pad_line(&line)
+            } else {
+                line
+            }
+        })
+    }
+
+    /// Pads each line of the given record types with spaces, or truncates it, to 80 characters.
+    pub fn pad_records<I, S>(self, records: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let filter = RecordFilter::keep(records);
+        self.map_lines(move |line| {
+            if filter.is_kept(&line) {
+                pad_line(&line)
+            } else {
+                line
+            }
+        })
+    }
+
+    /// Fills in missing element symbols (columns 77-78) of `ATOM` and `HETATM` lines, inferred with
+    /// [`crate::infer_element`] from the atom name field and the residue name.
+    pub fn infer_elements(self) -> Self {
+        let filter = RecordFilter::keep(["ATOM", "HETATM"]);
+        self.map_lines(move |mut line| {
+            if filter.is_kept(&line) {
+                fill_missing_element(&mut line);
+            }
+            line
+        })
+    }
+
+    /// Writes all remaining lines to `writer`, each followed by a newline.
+    pub fn write_to<W: Write>(self, mut writer: W) -> Result<(), PdbHandlerError> {
+        for line in self {
+            writeln!(writer, "{}", line?)?;
+        }
+        Ok(())
+    }
+
+    /// Turns the filter into a buffered reader over the transformed content, each line followed
+    /// by a newline.
+    ///
+    /// Errors are reported as `io::Error`s when reading; encoding and parse errors use
+    /// `io::ErrorKind::InvalidData` and wrap the original `PdbHandlerError`.
+    pub fn into_reader(self) -> BufReader<PdbLineReader<R>> {
+        BufReader::new(PdbLineReader {
+            filter: self,
+            buffer: Vec::new(),
+            position: 0,
+        })
+    }
+
+    /// Reads the next raw line from the underlying reader, without its line ending.
+    fn read_line(&mut self) -> Option<Result<String, PdbHandlerError>> {
+        let mut buffer = Vec::new();
+        match self.reader.read_until(b'\n', &mut buffer) {
+            Ok(0) => None,
+            Ok(_) => {
+                self.line_number += 1;
+                if buffer.ends_with(b"\n") {
+                    buffer.pop();
+                    if buffer.ends_with(b"\r") {
+                        buffer.pop();
+                    }
+                }
+                Some(
+                    String::from_utf8(buffer).map_err(|_| PdbHandlerError::Encoding {
+                        line: self.line_number,
+                    }),
+                )
+            }
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for PdbLineFilter<R> {
+    type Item = Result<String, PdbHandlerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            let line = match self.read_line() {
+                Some(Ok(line)) => line,
+                Some(Err(e)) => {
+                    self.finished = true;
+                    return Some(Err(e));
+                }
+                None => {
+                    self.finished = true;
+                    return None;
+                }
+            };
+
+            let mut line = Some(line);
+            for transform in self.transforms.iter_mut() {
+                line = match line {
+                    Some(line) => transform(line),
+                    None => break,
+                };
+            }
+
+            if line.is_some() {
+                return line.map(Ok);
+            }
+        }
+        None
+    }
+}
+
+/// A `Read` adaptor over a [`PdbLineFilter`], created with [`PdbLineFilter::into_reader`].
+pub struct PdbLineReader<R> {
+    filter: PdbLineFilter<R>,
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl<R: BufRead> Read for PdbLineReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // Refill the buffer with the next line once the current one is consumed
+        if self.position == self.buffer.len() {
+            self.buffer.clear();
+            self.position = 0;
+            match self.filter.next() {
+                Some(Ok(line)) => {
+                    self.buffer.extend(line.into_bytes());
+                    self.buffer.push(b'\n');
+                }
+                Some(Err(PdbHandlerError::Io(e))) => return Err(e),
+                Some(Err(e)) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+                None => return Ok(0),
+            }
+        }
+
+        let available = &self.buffer[self.position..];
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.position += n;
+        Ok(n)
+    }
+}
+
+/// Pads a line with spaces, or truncates it, to 80 characters.
+fn pad_line(line: &str) -> String {
+    format!("{:<80.80}", line)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let content = b"ATOM 1\r\nATOM 2\nEND";
+        let lines: Vec<String> = PdbLineFilter::new(&content[..])
+            .map(|line| line.unwrap())
+            .collect();
+
+        assert_eq!(lines, vec!["ATOM 1", "ATOM 2", "END"]);
+    }
+
+    #[test]
+    fn test_lines_invalid_utf8() {
+        let content = b"ATOM 1\nATOM \xff\nATOM 3\n";
+        let lines: Vec<_> = PdbLineFilter::new(&content[..]).collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(matches!(
+            lines[1],
+            Err(PdbHandlerError::Encoding { line: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_pipeline() {
+        let content = "REMARK 290\nATOM      1  N   GLY A  42\nHETATM    2  O   HOH A 101\nEND\n";
+        let mut output = Vec::new();
+        PdbLineFilter::new(content.as_bytes())
+            .remove_remarks()
+            .filter_lines(|line| !line.starts_with("HETATM"))
+            .pad()
+            .map_lines(|line| line.trim_end().to_string())
+            .write_to(&mut output)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "ATOM      1  N   GLY A  42\nEND\n"
+        );
+    }
+
+    #[test]
+    fn test_pad_records() {
+        let content = "ATOM      1  N   GLY A  42\nHETATM    2  O   HOH A 101\nTER\nEND\n";
+        let lines: Vec<String> = PdbLineFilter::new(content.as_bytes())
+            .pad()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(lines[0].len(), 80);
+        assert_eq!(lines[1].len(), 80);
+        assert_eq!(lines[2].len(), 80);
+        assert_eq!(lines[3], "END");
+
+        let lines: Vec<String> = PdbLineFilter::new(content.as_bytes())
+            .pad_records(["HETATM"])
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(lines[0], "ATOM      1  N   GLY A  42");
+        assert_eq!(lines[1].len(), 80);
+        assert_eq!(lines[2], "TER");
+
+        // Serials over 99999 run into the record name
+        let content = "ATOM 100000  CA  GLY A  42\nHETATM100001  O   HOH A 101\n";
+        let lines: Vec<String> = PdbLineFilter::new(content.as_bytes())
+            .pad()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(lines[0].len(), 80);
+        assert_eq!(lines[1].len(), 80);
+        assert!(lines[0].starts_with("ATOM 100000  CA  GLY A  42"));
+    }
+
+    #[test]
+    fn test_infer_elements() {
+        let content = "ATOM      1  CA  ALA A   1      16.967  12.784   4.338  1.00 10.80\nHETATM    2 CA    CA A 101      10.000  10.000  10.000  1.00 20.00\nEND\n";
+        let lines: Vec<String> = PdbLineFilter::new(content.as_bytes())
+            .infer_elements()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(&lines[0][76..78], " C");
+        assert_eq!(&lines[1][76..78], "CA");
+        assert_eq!(lines[2], "END");
+    }
+
+    #[test]
+    fn test_into_reader() {
+        let content = "REMARK 290\nATOM      1  N   GLY A  42\n";
+        let mut reader = PdbLineFilter::new(content.as_bytes())
+            .remove_remarks()
+            .pad()
+            .into_reader();
+
+        let mut output = String::new();
+        reader.read_to_string(&mut output).unwrap();
+
+        assert_eq!(output.len(), 81);
+        assert!(output.starts_with("ATOM      1  N   GLY A  42 "));
+    }
+
+    #[test]
+    fn test_into_reader_invalid_utf8() {
+        let content = b"ATOM 1\nATOM \xff\n";
+        let mut output = String::new();
+        let error = PdbLineFilter::new(&content[..])
+            .into_reader()
+            .read_to_string(&mut output)
+            .unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(matches!(
+            error.get_ref().unwrap().downcast_ref::<PdbHandlerError>(),
+            Some(PdbHandlerError::Encoding { line: 2 })
+        ));
+    }
+}
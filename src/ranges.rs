@@ -0,0 +1,546 @@
+use crate::{PdbHandlerError, ResidueId};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// A range of consecutive residues, from `start` to `end` inclusive.
+///
+/// A range either steps through serial numbers without insertion codes, e.g. `1-45`, or through
+/// the insertion codes of a single serial number, e.g. `130A-130C` or `52-52B`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResidueRange {
+    /// The first residue of the range.
+    pub start: ResidueId,
+    /// The last residue of the range.
+    pub end: ResidueId,
+}
+
+impl ResidueRange {
+    /// Returns the residue IDs in the range, in order.
+    pub fn residues(&self) -> impl Iterator<Item = ResidueId> {
+        let end = self.end;
+        std::iter::successors(Some(self.start), move |current| {
+            if *current >= end {
+                None
+            } else if current.serial == end.serial {
+                let icode = match current.icode {
+                    Some(code) => char::from_u32(code as u32 + 1),
+                    None => end.icode.map(first_icode),
+                };
+                Some(ResidueId::new(current.serial, icode))
+            } else {
+                Some(ResidueId::new(current.serial + 1, None))
+            }
+        })
+    }
+}
+
+impl fmt::Display for ResidueRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", self.start)
+        } else {
+            write!(f, "{}-{}", self.start, self.end)
+        }
+    }
+}
+
+impl FromStr for ResidueRange {
+    type Err = PdbHandlerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RANGE: OnceLock<Regex> = OnceLock::new();
+        let re = RANGE.get_or_init(|| {
+            Regex::new(r"^\s*(-?\d+[A-Za-z]?)\s*(?:-\s*(-?\d+[A-Za-z]?))?\s*$").unwrap()
+        });
+
+        let invalid = || PdbHandlerError::Parse(format!("invalid residue range: {:?}", s));
+        let captures = re.captures(s).ok_or_else(invalid)?;
+
+        let start: ResidueId = captures[1].parse()?;
+        let end: ResidueId = match captures.get(2) {
+            Some(end) => end.as_str().parse()?,
+            None => start,
+        };
+
+        // Ranges over several serial numbers cannot step through insertion codes
+        let same_serial = start.serial == end.serial;
+        if start > end || (!same_serial && (start.icode.is_some() || end.icode.is_some())) {
+            return Err(invalid());
+        }
+
+        Ok(ResidueRange { start, end })
+    }
+}
+
+/// Sorted, disjoint and non-adjacent inclusive intervals.
+type Intervals = Vec<(isize, isize)>;
+
+/// The residues of a single chain.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ChainRanges {
+    /// The serial numbers of the residues without an insertion code.
+    serials: Intervals,
+    /// The insertion codes, as code points, of the residues with one, per serial number.
+    insertions: BTreeMap<isize, Intervals>,
+}
+
+impl ChainRanges {
+    fn add(&mut self, range: ResidueRange) {
+        let (start, end) = (range.start, range.end);
+        match (start.icode, end.icode) {
+            (None, None) => add_interval(&mut self.serials, (start.serial, end.serial)),
+            (None, Some(last)) => {
+                add_interval(&mut self.serials, (start.serial, start.serial));
+                let first = first_icode(last);
+                add_interval(
+                    self.insertions.entry(start.serial).or_default(),
+                    (first as isize, last as isize),
+                );
+            }
+            (Some(first), last) => add_interval(
+                self.insertions.entry(start.serial).or_default(),
+                (first as isize, last.unwrap_or(first) as isize),
+            ),
+        }
+    }
+
+    fn contains(&self, residue: ResidueId) -> bool {
+        match residue.icode {
+            None => contains(&self.serials, residue.serial),
+            Some(icode) => self
+                .insertions
+                .get(&residue.serial)
+                .is_some_and(|codes| contains(codes, icode as isize)),
+        }
+    }
+
+    fn len(&self) -> usize {
+        let count = |intervals: &Intervals| {
+            intervals
+                .iter()
+                .map(|(a, b)| b.abs_diff(*a).saturating_add(1))
+                .fold(0usize, usize::saturating_add)
+        };
+        self.insertions
+            .values()
+            .map(count)
+            .fold(count(&self.serials), usize::saturating_add)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.serials.is_empty() && self.insertions.is_empty()
+    }
+
+    fn ranges(&self) -> Vec<ResidueRange> {
+        let icode = |code: isize| char::from_u32(code as u32);
+        let mut pieces: Vec<ResidueRange> = self
+            .serials
+            .iter()
+            .map(|(a, b)| ResidueRange {
+                start: ResidueId::new(*a, None),
+                end: ResidueId::new(*b, None),
+            })
+            .chain(self.insertions.iter().flat_map(|(serial, codes)| {
+                codes.iter().map(move |(a, b)| ResidueRange {
+                    start: ResidueId::new(*serial, icode(*a)),
+                    end: ResidueId::new(*serial, icode(*b)),
+                })
+            }))
+            .collect();
+        pieces.sort_by_key(|range| range.start);
+
+        // A single residue followed by its insertions from `A` is written as one range, e.g. 52-52B
+        let mut ranges: Vec<ResidueRange> = Vec::with_capacity(pieces.len());
+        for piece in pieces {
+            if let (Some(last), Some(first)) = (ranges.last_mut(), piece.start.icode) {
+                let single = last.start == last.end && last.end.icode.is_none();
+                if single
+                    && last.end.serial == piece.start.serial
+                    && piece.end.icode.map(first_icode) == Some(first)
+                {
+                    last.end = piece.end;
+                    continue;
+                }
+            }
+            ranges.push(piece);
+        }
+        ranges
+    }
+
+    fn combine<F>(&self, other: &ChainRanges, op: F) -> ChainRanges
+    where
+        F: Fn(&Intervals, &Intervals) -> Intervals,
+    {
+        let empty = Intervals::new();
+        let insertions = self
+            .insertions
+            .iter()
+            .map(|(serial, codes)| {
+                let others = other.insertions.get(serial).unwrap_or(&empty);
+                (*serial, op(codes, others))
+            })
+            .filter(|(_, codes)| !codes.is_empty())
+            .collect();
+        ChainRanges {
+            serials: op(&self.serials, &other.serials),
+            insertions,
+        }
+    }
+}
+
+/// A set of residues per chain, written as compact residue ranges.
+///
+/// The text form lists the chains separated by `;`, each with its ranges separated by `,`, e.g.
+/// `A:1-45,47-120,130A-130C;B:1-10`. Chains are written in alphabetical order, and consecutive
+/// residues are merged into ranges, see [`ResidueRange`]. The residues are stored as ranges, so
+/// large ranges take no more space than small ones.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::ResidueRanges;
+///
+/// let selection: ResidueRanges = "A:1-10,52-52B;B:5".parse().unwrap();
+/// let interface: ResidueRanges = "A:8-20;B:1-10".parse().unwrap();
+///
+/// assert_eq!(selection.intersection(&interface).to_string(), "A:8-10;B:5");
+/// assert_eq!(selection.difference(&interface).to_string(), "A:1-7,52-52B");
+/// assert_eq!(selection.union(&interface).to_string(), "A:1-20,52-52B;B:1-10");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResidueRanges {
+    chains: BTreeMap<String, ChainRanges>,
+}
+
+impl ResidueRanges {
+    /// Creates an empty set of residues.
+    pub fn new() -> Self {
+        ResidueRanges::default()
+    }
+
+    /// Creates the set of all residues of the given PDB structure.
+    pub fn from_structure(structure: &pdbtbx::PDB) -> Self {
+        let mut ranges = ResidueRanges::new();
+        for chain in structure.chains() {
+            for residue in chain.residues() {
+                ranges.insert(chain.id(), ResidueId::from_residue(residue));
+            }
+        }
+        ranges
+    }
+
+    /// Adds a residue to the set. Returns true if it was not present yet.
+    pub fn insert(&mut self, chain: &str, residue: ResidueId) -> bool {
+        if self.contains(chain, residue) {
+            return false;
+        }
+        self.insert_range(
+            chain,
+            ResidueRange {
+                start: residue,
+                end: residue,
+            },
+        );
+        true
+    }
+
+    /// Adds all residues of a range to the set.
+    pub fn insert_range(&mut self, chain: &str, range: ResidueRange) {
+        self.chains.entry(chain.to_string()).or_default().add(range);
+    }
+
+    /// Returns true if the set contains the given residue.
+    pub fn contains(&self, chain: &str, residue: ResidueId) -> bool {
+        self.chains
+            .get(chain)
+            .is_some_and(|ranges| ranges.contains(residue))
+    }
+
+    /// Returns the number of residues in the set.
+    pub fn len(&self) -> usize {
+        self.chains
+            .values()
+            .map(|ranges| ranges.len())
+            .fold(0, usize::saturating_add)
+    }
+
+    /// Returns true if the set contains no residues.
+    pub fn is_empty(&self) -> bool {
+        self.chains.values().all(|ranges| ranges.is_empty())
+    }
+
+    /// Returns the IDs of the chains with residues in the set, in alphabetical order.
+    pub fn chains(&self) -> impl Iterator<Item = &str> {
+        self.chains
+            .iter()
+            .filter(|(_, ranges)| !ranges.is_empty())
+            .map(|(chain, _)| chain.as_str())
+    }
+
+    /// Returns the residues of the given chain, range by range.
+    pub fn residues(&self, chain: &str) -> impl Iterator<Item = ResidueId> {
+        self.ranges(chain)
+            .into_iter()
+            .flat_map(|range| range.residues())
+    }
+
+    /// Returns the residues of the given chain merged into ranges, sorted by their first residue.
+    pub fn ranges(&self, chain: &str) -> Vec<ResidueRange> {
+        self.chains
+            .get(chain)
+            .map(|ranges| ranges.ranges())
+            .unwrap_or_default()
+    }
+
+    /// Returns the residues that are in `self` or in `other`.
+    pub fn union(&self, other: &ResidueRanges) -> ResidueRanges {
+        let mut union = self.clone();
+        for (chain, ranges) in &other.chains {
+            let target = union.chains.entry(chain.clone()).or_default();
+            target.serials = union_intervals(&target.serials, &ranges.serials);
+            for (serial, codes) in &ranges.insertions {
+                let target_codes = target.insertions.entry(*serial).or_default();
+                *target_codes = union_intervals(target_codes, codes);
+            }
+        }
+        union
+    }
+
+    /// Returns the residues that are in both `self` and `other`.
+    pub fn intersection(&self, other: &ResidueRanges) -> ResidueRanges {
+        self.combine(other, intersect_intervals)
+    }
+
+    /// Returns the residues that are in `self` but not in `other`.
+    pub fn difference(&self, other: &ResidueRanges) -> ResidueRanges {
+        self.combine(other, subtract_intervals)
+    }
+
+    fn combine<F>(&self, other: &ResidueRanges, op: F) -> ResidueRanges
+    where
+        F: Fn(&Intervals, &Intervals) -> Intervals,
+    {
+        let empty = ChainRanges::default();
+        let chains = self
+            .chains
+            .iter()
+            .map(|(chain, ranges)| {
+                let others = other.chains.get(chain).unwrap_or(&empty);
+                (chain.clone(), ranges.combine(others, &op))
+            })
+            .filter(|(_, ranges)| !ranges.is_empty())
+            .collect();
+        ResidueRanges { chains }
+    }
+}
+
+/// Returns the insertion code that a range ending at `last` starts from after the residue
+/// without one: `A`, or `a` for lowercase codes.
+fn first_icode(last: char) -> char {
+    if last.is_ascii_lowercase() {
+        'a'
+    } else {
+        'A'
+    }
+}
+
+/// Returns true if `value` is in one of the intervals.
+fn contains(intervals: &Intervals, value: isize) -> bool {
+    let i = intervals.partition_point(|(_, end)| *end < value);
+    intervals.get(i).is_some_and(|(start, _)| *start <= value)
+}
+
+/// Adds an interval, merging it with the ones it overlaps or touches.
+fn add_interval(intervals: &mut Intervals, interval: (isize, isize)) {
+    *intervals = union_intervals(intervals, &vec![interval]);
+}
+
+fn union_intervals(a: &Intervals, b: &Intervals) -> Intervals {
+    let mut all: Intervals = a.iter().chain(b).copied().collect();
+    all.sort();
+
+    let mut merged: Intervals = Vec::with_capacity(all.len());
+    for (start, end) in all {
+        match merged.last_mut() {
+            Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+fn intersect_intervals(a: &Intervals, b: &Intervals) -> Intervals {
+    let mut intersection = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let start = a[i].0.max(b[j].0);
+        let end = a[i].1.min(b[j].1);
+        if start <= end {
+            intersection.push((start, end));
+        }
+        if a[i].1 < b[j].1 {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    intersection
+}
+
+fn subtract_intervals(a: &Intervals, b: &Intervals) -> Intervals {
+    let mut difference = Vec::new();
+    let mut j = 0;
+    for &(a_start, a_end) in a {
+        while j < b.len() && b[j].1 < a_start {
+            j += 1;
+        }
+
+        // Walk the intervals of `b` that overlap this one, keeping the gaps between them
+        let mut start = a_start;
+        let mut covered = false;
+        let mut k = j;
+        while k < b.len() && b[k].0 <= a_end {
+            if b[k].0 > start {
+                difference.push((start, b[k].0 - 1));
+            }
+            if b[k].1 >= a_end {
+                covered = true;
+                break;
+            }
+            start = b[k].1 + 1;
+            k += 1;
+        }
+        if !covered {
+            difference.push((start, a_end));
+        }
+    }
+    difference
+}
+
+impl fmt::Display for ResidueRanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chains: Vec<String> = self
+            .chains()
+            .map(|chain| {
+                let ranges: Vec<String> = self
+                    .ranges(chain)
+                    .iter()
+                    .map(|range| range.to_string())
+                    .collect();
+                format!("{}:{}", chain, ranges.join(","))
+            })
+            .collect();
+        write!(f, "{}", chains.join(";"))
+    }
+}
+
+impl FromStr for ResidueRanges {
+    type Err = PdbHandlerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ranges = ResidueRanges::new();
+
+        for part in s.split(';').filter(|part| !part.trim().is_empty()) {
+            let (chain, residues) = part
+                .split_once(':')
+                .ok_or_else(|| PdbHandlerError::Parse(format!("missing chain ID: {:?}", part)))?;
+            let chain = chain.trim();
+            if chain.is_empty() || chain.contains(',') {
+                return Err(PdbHandlerError::Parse(format!(
+                    "invalid chain ID: {:?}",
+                    chain
+                )));
+            }
+
+            for range in residues.split(',') {
+                ranges.insert_range(chain, range.parse()?);
+            }
+        }
+
+        Ok(ranges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use pdbtbx::ReadOptions;
+
+    #[test]
+    fn test_residue_ranges_round_trip() {
+        let text = "A:-5--3,1-45,47-120,130A-130C;B:52-52B,60-61";
+        let ranges: ResidueRanges = text.parse().unwrap();
+
+        assert_eq!(ranges.to_string(), text);
+        assert_eq!(ranges.len(), 3 + 45 + 74 + 3 + 3 + 2);
+        assert_eq!(ranges.residues("A").count(), 3 + 45 + 74 + 3);
+        assert!(ranges.contains("A", ResidueId::new(130, Some('B'))));
+        assert!(!ranges.contains("A", ResidueId::new(130, None)));
+        assert!(!ranges.contains("A", ResidueId::new(46, None)));
+        assert!(!ranges.contains("C", ResidueId::new(1, None)));
+        assert_eq!(ranges.chains().collect::<Vec<_>>(), vec!["A", "B"]);
+
+        // Ranges are merged and sorted when written
+        let ranges: ResidueRanges = " B:3, 1-2 ; A:5;A:4;A:4A ".parse().unwrap();
+        assert_eq!(ranges.to_string(), "A:4-5,4A;B:1-3");
+        assert!("".parse::<ResidueRanges>().unwrap().is_empty());
+
+        for invalid in ["1-45", "A:", "A:10-1", "A:1A-3", "A:1-x", ":1"] {
+            assert!(
+                matches!(
+                    invalid.parse::<ResidueRanges>(),
+                    Err(PdbHandlerError::Parse(_))
+                ),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_residue_ranges_set_operations() {
+        let a: ResidueRanges = "A:1-10,20-30,25-25C;B:1-5".parse().unwrap();
+        let b: ResidueRanges = "A:5-25,25B;C:1".parse().unwrap();
+
+        assert_eq!(a.union(&b).to_string(), "A:1-30,25A-25C;B:1-5;C:1");
+        assert_eq!(a.intersection(&b).to_string(), "A:5-10,20-25,25B");
+        assert_eq!(a.difference(&b).to_string(), "A:1-4,25A,25C,26-30;B:1-5");
+        assert_eq!(b.difference(&a).to_string(), "A:11-19;C:1");
+        assert!(a.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn test_residue_ranges_large() {
+        let ranges: ResidueRanges = "A:1-20000000".parse().unwrap();
+        let hole: ResidueRanges = "A:5,10-19999999".parse().unwrap();
+
+        assert_eq!(ranges.len(), 20_000_000);
+        assert!(ranges.contains("A", ResidueId::new(19_999_999, None)));
+        assert_eq!(ranges.difference(&hole).to_string(), "A:1-4,6-9,20000000");
+
+        let all = format!("A:{}-{}", isize::MIN, isize::MAX);
+        let all: ResidueRanges = all.parse().unwrap();
+        assert_eq!(all.len(), usize::MAX);
+        assert_eq!(all.intersection(&ranges), ranges);
+    }
+
+    #[test]
+    fn test_residue_ranges_from_structure() {
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/chains.pdb")
+            .unwrap();
+
+        let ranges = ResidueRanges::from_structure(&structure);
+
+        assert_eq!(ranges.len(), structure.residue_count());
+        for chain in structure.chains() {
+            let first = ResidueId::from_residue(chain.residues().next().unwrap());
+            assert!(ranges.contains(chain.id(), first));
+        }
+    }
+}
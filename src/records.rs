@@ -0,0 +1,126 @@
+/// A filter on PDB record types, built from a keep-list or a drop-list.
+///
+/// Records are given by name, e.g. `ATOM`, `HETATM` or `ANISOU`, and matched against columns
+/// 1-6 of each line. `REMARK` records can be narrowed down to a single remark number by giving
+/// it after the record name, e.g. `REMARK 350`. Record names are case-insensitive.
+///
+/// # Examples
+///
+/// ```
+/// use pdb_handler::RecordFilter;
+///
+/// let filter = RecordFilter::drop(["ANISOU", "CONECT", "REMARK 350"]);
+/// assert!(!filter.is_kept("ANISOU    1  N   THR A   1     2406   1997   1808"));
+/// assert!(!filter.is_kept("REMARK 350 BIOMOLECULE: 1"));
+/// assert!(filter.is_kept("REMARK 290 CRYSTALLOGRAPHIC SYMMETRY"));
+///
+/// let filter = RecordFilter::keep(["ATOM", "TER", "END"]);
+/// assert!(filter.is_kept("ATOM      1  N   THR A   1      17.047  14.099   3.625"));
+/// assert!(!filter.is_kept("HETATM    2  O   HOH A 101       1.000   1.000   1.000"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordFilter {
+    keep: bool,
+    records: Vec<RecordSpec>,
+}
+
+/// A record name, optionally restricted to a `REMARK` number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RecordSpec {
+    name: String,
+    number: Option<String>,
+}
+
+impl RecordFilter {
+    /// Creates a filter that keeps only the given records and drops all other lines.
+    pub fn keep<I, S>(records: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        RecordFilter {
+            keep: true,
+            records: records
+                .into_iter()
+                .map(|r| RecordSpec::new(r.as_ref()))
+                .collect(),
+        }
+    }
+
+    /// Creates a filter that drops the given records and keeps all other lines.
+    pub fn drop<I, S>(records: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        RecordFilter {
+            keep: false,
+            records: records
+                .into_iter()
+                .map(|r| RecordSpec::new(r.as_ref()))
+                .collect(),
+        }
+    }
+
+    /// Returns true if the given line passes the filter.
+    pub fn is_kept(&self, line: &str) -> bool {
+        let listed = self.records.iter().any(|record| record.matches(line));
+        listed == self.keep
+    }
+}
+
+impl RecordSpec {
+    fn new(spec: &str) -> Self {
+        let mut parts = spec.split_whitespace();
+        RecordSpec {
+            name: parts.next().unwrap_or("").to_uppercase(),
+            number: parts.next().map(|number| number.to_string()),
+        }
+    }
+
+    fn matches(&self, line: &str) -> bool {
+        let record: String = line.chars().take(6).collect();
+        if !record.trim_end().eq_ignore_ascii_case(&self.name) {
+            return false;
+        }
+
+        match &self.number {
+            // The remark number is right-justified in columns 8-10
+            Some(number) => {
+                let remark: String = line.chars().skip(6).take(4).collect();
+                remark.trim() == number
+            }
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_record_filter_drop() {
+        let filter = RecordFilter::drop(["hetatm", "REMARK 350"]);
+
+        assert!(filter.is_kept("ATOM      1  N   GLY A  42"));
+        assert!(filter.is_kept("REMARK   2 RESOLUTION. 1.50 ANGSTROMS."));
+        assert!(filter.is_kept("REMARK  35"));
+        assert!(!filter.is_kept("REMARK 350 BIOMOLECULE: 1"));
+        assert!(!filter.is_kept("HETATM    2  O   HOH A 101"));
+        assert!(!filter.is_kept("HETATM"));
+    }
+
+    #[test]
+    fn test_record_filter_keep() {
+        let filter = RecordFilter::keep(["ATOM", "TER", "END"]);
+
+        assert!(filter.is_kept("ATOM  12345  N   GLY A  42"));
+        assert!(filter.is_kept("TER"));
+        assert!(filter.is_kept("END   "));
+        assert!(!filter.is_kept("ENDMDL"));
+        assert!(!filter.is_kept("ANISOU    1  N   GLY A  42"));
+        assert!(!filter.is_kept(""));
+    }
+}
@@ -0,0 +1,321 @@
+use crate::{ResidueId, ResidueRanges};
+use std::collections::{BTreeMap, HashMap};
+
+/// A mapping from old to new residue IDs per chain, as returned by the renumbering functions.
+///
+/// The mapping can be used to translate residue lists and selections that refer to the old
+/// numbering, e.g. restraint files or the output of [`crate::identify_residue_numbers`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResidueMapping {
+    chains: BTreeMap<String, BTreeMap<ResidueId, ResidueId>>,
+}
+
+impl ResidueMapping {
+    /// Creates an empty mapping.
+    pub fn new() -> Self {
+        ResidueMapping::default()
+    }
+
+    /// Maps residue `old` of the given chain to `new`, replacing any previous mapping.
+    pub fn insert(&mut self, chain: &str, old: ResidueId, new: ResidueId) {
+        self.chains
+            .entry(chain.to_string())
+            .or_default()
+            .insert(old, new);
+    }
+
+    /// Returns the new ID of residue `old` of the given chain, if it is mapped.
+    pub fn get(&self, chain: &str, old: ResidueId) -> Option<ResidueId> {
+        self.chains.get(chain)?.get(&old).copied()
+    }
+
+    /// Returns the number of mapped residues.
+    pub fn len(&self) -> usize {
+        self.chains.values().map(|residues| residues.len()).sum()
+    }
+
+    /// Returns true if no residues are mapped.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the mapped residues as `(chain, old, new)`, by chain and then by old residue ID.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, ResidueId, ResidueId)> + '_ {
+        self.chains.iter().flat_map(|(chain, residues)| {
+            residues
+                .iter()
+                .map(move |(old, new)| (chain.as_str(), *old, *new))
+        })
+    }
+
+    /// Returns the mapping from new to old residue IDs, to undo a renumbering.
+    pub fn inverse(&self) -> ResidueMapping {
+        let mut inverse = ResidueMapping::new();
+        for (chain, old, new) in self.iter() {
+            inverse.insert(chain, new, old);
+        }
+        inverse
+    }
+
+    /// Translates a set of residues to the new numbering. Residues that are not mapped are dropped.
+    pub fn translate_ranges(&self, ranges: &ResidueRanges) -> ResidueRanges {
+        let mut translated = ResidueRanges::new();
+        for (chain, old, new) in self.iter() {
+            if ranges.contains(chain, old) {
+                translated.insert(chain, new);
+            }
+        }
+        translated
+    }
+
+    /// Translates residue numbers given as strings, e.g. the output of
+    /// [`crate::identify_residue_numbers`], to the new numbering. The order of the residues is kept;
+    /// residues that are not mapped or cannot be parsed as a [`ResidueId`] are dropped.
+    pub fn translate_residue_numbers(
+        &self,
+        residue_numbers: &HashMap<String, Vec<String>>,
+    ) -> HashMap<String, Vec<String>> {
+        residue_numbers
+            .iter()
+            .map(|(chain, numbers)| {
+                let numbers = numbers
+                    .iter()
+                    .filter_map(|number| number.parse().ok())
+                    .filter_map(|old| self.get(chain, old))
+                    .map(|new| new.to_string())
+                    .collect();
+                (chain.clone(), numbers)
+            })
+            .collect()
+    }
+}
+
+/// Renumbers the residues of each chain sequentially from `start`, removing insertion codes.
+///
+/// Residues are numbered in file order, including waters and ligands, so that a chain numbered
+/// `5, 6, 6A, 6B, 7` with `start` 1 becomes `1, 2, 3, 4, 5`.
+///
+/// # Arguments
+///
+/// * `structure` - A mutable reference to the `pdbtbx::PDB` structure to renumber.
+/// * `start` - The number of the first residue of each chain.
+///
+/// # Returns
+///
+/// The mapping from the old to the new residue IDs. Residues whose new number would overflow an
+/// `isize` are left unchanged and are not in the mapping.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::{renumber_sequential, ResidueId};
+///
+/// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+/// let mapping = renumber_sequential(&mut pdb, 101);
+///
+/// assert_eq!(mapping.get("A", ResidueId::new(1, None)), Some(ResidueId::new(101, None)));
+/// ```
+///
+/// # Notes
+///
+/// All models are renumbered the same way: a residue that appears in several models keeps the
+/// same new ID in each of them.
+pub fn renumber_sequential(structure: &mut pdbtbx::PDB, start: isize) -> ResidueMapping {
+    let mut next: HashMap<String, Option<isize>> = HashMap::new();
+    renumber_by(structure, |chain, _| {
+        let next = next.entry(chain.to_string()).or_insert(Some(start));
+        let serial = (*next)?;
+        *next = serial.checked_add(1);
+        Some(ResidueId::new(serial, None))
+    })
+}
+
+/// Shifts the residue numbers of all chains by `offset`, keeping the insertion codes.
+///
+/// # Arguments
+///
+/// * `structure` - A mutable reference to the `pdbtbx::PDB` structure to renumber.
+/// * `offset` - The number to add to every residue number, e.g. `-10` or `100`.
+///
+/// # Returns
+///
+/// The mapping from the old to the new residue IDs. Residues whose new number would overflow an
+/// `isize` are left unchanged and are not in the mapping.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::{renumber_offset, ResidueId};
+///
+/// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+/// let mapping = renumber_offset(&mut pdb, 100);
+///
+/// assert_eq!(mapping.get("A", ResidueId::new(46, None)), Some(ResidueId::new(146, None)));
+/// ```
+pub fn renumber_offset(structure: &mut pdbtbx::PDB, offset: isize) -> ResidueMapping {
+    renumber_by(structure, |_, old| {
+        let serial = old.serial.checked_add(offset)?;
+        Some(ResidueId::new(serial, old.icode))
+    })
+}
+
+/// Renumbers the residues of the given PDB structure with an existing mapping, e.g. one returned
+/// by another renumbering function or its [`ResidueMapping::inverse`].
+///
+/// # Arguments
+///
+/// * `structure` - A mutable reference to the `pdbtbx::PDB` structure to renumber.
+/// * `mapping` - The mapping from the current to the new residue IDs.
+///
+/// # Returns
+///
+/// The number of residues that were renumbered. Residues that are not in the mapping are left
+/// unchanged.
+pub fn renumber_with(structure: &mut pdbtbx::PDB, mapping: &ResidueMapping) -> usize {
+    let mut renumbered = 0;
+    for chain in structure.chains_mut() {
+        let chain_id = chain.id().to_string();
+        for residue in chain.residues_mut() {
+            if let Some(new) = mapping.get(&chain_id, ResidueId::from_residue(residue)) {
+                set_residue_id(residue, new);
+                renumbered += 1;
+            }
+        }
+    }
+    renumbered
+}
+
+/// Renumbers every residue with the ID returned by `new_id` for its chain and current ID, and
+/// returns the mapping. `new_id` is called once per distinct residue; residues for which it
+/// returns `None` are left unchanged.
+pub(crate) fn renumber_by<F>(structure: &mut pdbtbx::PDB, mut new_id: F) -> ResidueMapping
+where
+    F: FnMut(&str, ResidueId) -> Option<ResidueId>,
+{
+    let mut mapping = ResidueMapping::new();
+    let mut seen: HashMap<(String, ResidueId), Option<ResidueId>> = HashMap::new();
+
+    for chain in structure.chains_mut() {
+        let chain_id = chain.id().to_string();
+        for residue in chain.residues_mut() {
+            let old = ResidueId::from_residue(residue);
+            let new = *seen
+                .entry((chain_id.clone(), old))
+                .or_insert_with(|| new_id(&chain_id, old));
+
+            if let Some(new) = new {
+                set_residue_id(residue, new);
+                mapping.insert(&chain_id, old, new);
+            }
+        }
+    }
+
+    mapping
+}
+
+fn set_residue_id(residue: &mut pdbtbx::Residue, id: ResidueId) {
+    residue.set_serial_number(id.serial);
+    match id.icode {
+        Some(icode) => {
+            residue.set_insertion_code(icode.to_string());
+        }
+        None => residue.remove_insertion_code(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::ResidueOrder;
+    use crate::{identify_residue_ids, identify_residue_numbers};
+    use pdbtbx::ReadOptions;
+
+    fn read_structure() -> pdbtbx::PDB {
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/renumber.pdb")
+            .unwrap();
+        structure
+    }
+
+    #[test]
+    fn test_renumber_sequential() {
+        let mut structure = read_structure();
+        let residue_numbers = identify_residue_numbers(&structure);
+        let ranges: ResidueRanges = "A:6-6B,100;B:11".parse().unwrap();
+
+        let mapping = renumber_sequential(&mut structure, 1);
+
+        assert_eq!(mapping.len(), 8);
+        assert_eq!(
+            mapping.get("A", ResidueId::new(6, Some('B'))),
+            Some(ResidueId::new(4, None))
+        );
+        let ids = identify_residue_ids(&structure, ResidueOrder::File);
+        assert_eq!(
+            ids["A"],
+            (1..=6).map(|n| ResidueId::new(n, None)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            ids["B"],
+            vec![ResidueId::new(1, None), ResidueId::new(2, None)]
+        );
+
+        assert_eq!(mapping.translate_ranges(&ranges).to_string(), "A:2-4,6;B:2");
+        let translated = mapping.translate_residue_numbers(&residue_numbers);
+        assert_eq!(translated["B"], vec!["1", "2"]);
+
+        // The inverse mapping restores the original numbering
+        assert_eq!(renumber_with(&mut structure, &mapping.inverse()), 8);
+        assert_eq!(identify_residue_ids(&structure, ResidueOrder::File), {
+            let original = read_structure();
+            identify_residue_ids(&original, ResidueOrder::File)
+        });
+
+        // Only the first residue of each chain can be numbered without overflowing
+        let mut structure = read_structure();
+        let mapping = renumber_sequential(&mut structure, isize::MAX);
+
+        assert_eq!(mapping.len(), 2);
+        assert_eq!(
+            mapping.get("B", ResidueId::new(10, None)),
+            Some(ResidueId::new(isize::MAX, None))
+        );
+        assert_eq!(mapping.get("B", ResidueId::new(11, None)), None);
+    }
+
+    #[test]
+    fn test_renumber_offset() {
+        let mut structure = read_structure();
+
+        let mapping = renumber_offset(&mut structure, -4);
+
+        assert_eq!(
+            mapping.get("A", ResidueId::new(6, Some('A'))),
+            Some(ResidueId::new(2, Some('A')))
+        );
+        assert_eq!(mapping.get("C", ResidueId::new(6, None)), None);
+        let ids = identify_residue_ids(&structure, ResidueOrder::File);
+        assert_eq!(ids["A"][0], ResidueId::new(1, None));
+        assert_eq!(ids["A"][3], ResidueId::new(2, Some('B')));
+        assert_eq!(
+            ids["B"],
+            vec![ResidueId::new(6, None), ResidueId::new(7, None)]
+        );
+
+        // Residues that would overflow keep their number and are not mapped
+        let mut structure = read_structure();
+        let mapping = renumber_offset(&mut structure, isize::MAX - 6);
+
+        assert_eq!(mapping.len(), 4);
+        assert_eq!(
+            mapping.get("A", ResidueId::new(6, Some('B'))),
+            Some(ResidueId::new(isize::MAX, Some('B')))
+        );
+        assert_eq!(mapping.get("A", ResidueId::new(7, None)), None);
+        let ids = identify_residue_ids(&structure, ResidueOrder::File);
+        assert_eq!(ids["A"][4], ResidueId::new(7, None));
+        assert_eq!(ids["B"][0], ResidueId::new(10, None));
+    }
+}
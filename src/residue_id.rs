@@ -0,0 +1,210 @@
+use crate::PdbHandlerError;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+/// A residue identifier: the residue serial number and its insertion code.
+///
+/// Residue IDs sort numerically by serial number, then by insertion code, with the residue without
+/// an insertion code first, e.g. `9`, `10`, `52`, `52A`, `52B`, `53`. They are displayed and
+/// parsed in the PDB style, e.g. `52A`.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::ResidueId;
+///
+/// let id: ResidueId = "52A".parse().unwrap();
+/// assert_eq!(id, ResidueId::new(52, Some('A')));
+/// assert!(ResidueId::new(9, None) < ResidueId::new(10, None));
+/// assert_eq!(id.to_string(), "52A");
+/// ```
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ResidueId {
+    /// The residue serial number.
+    pub serial: isize,
+    /// The residue insertion code, if any.
+    pub icode: Option<char>,
+}
+
+impl ResidueId {
+    /// Creates a residue ID from a serial number and an optional insertion code.
+    pub fn new(serial: isize, icode: Option<char>) -> Self {
+        ResidueId { serial, icode }
+    }
+
+    /// Returns the ID of the given residue.
+    pub fn from_residue(residue: &pdbtbx::Residue) -> Self {
+        ResidueId {
+            serial: residue.serial_number(),
+            icode: residue
+                .insertion_code()
+                .and_then(|code| code.chars().next()),
+        }
+    }
+}
+
+impl fmt::Display for ResidueId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.icode {
+            Some(icode) => write!(f, "{}{}", self.serial, icode),
+            None => write!(f, "{}", self.serial),
+        }
+    }
+}
+
+impl FromStr for ResidueId {
+    type Err = PdbHandlerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (number, icode) = match s.chars().last() {
+            Some(last) if last.is_ascii_alphabetic() => (&s[..s.len() - 1], Some(last)),
+            _ => (s, None),
+        };
+        let serial = number
+            .parse()
+            .map_err(|_| PdbHandlerError::Parse(format!("invalid residue ID: {:?}", s)))?;
+
+        Ok(ResidueId { serial, icode })
+    }
+}
+
+/// The order of the residues returned by [`identify_residue_ids`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResidueOrder {
+    /// The order in which the residues appear in the file.
+    File,
+    /// Numeric order of the serial numbers, then of the insertion codes.
+    Numeric,
+}
+
+/// Identifies the residue IDs, with their insertion codes, in each chain of the given PDB structure.
+///
+/// Unlike [`crate::identify_residue_numbers`], residues with the same serial number and different
+/// insertion codes (e.g. `52A` and `52B`) are kept apart, and numeric order is used, so `9` comes
+/// before `10`.
+///
+/// # Arguments
+///
+/// * `structure` - A reference to a `pdbtbx::PDB` structure representing the PDB file to be analyzed.
+/// * `order` - Whether to return the residues in file order or in numeric order.
+///
+/// # Returns
+///
+/// A `HashMap<String, Vec<ResidueId>>` where each key is a chain ID and each value is a vector of
+/// the unique residue IDs found in that chain.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::{identify_residue_ids, ResidueOrder};
+///
+/// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+/// let residue_ids = identify_residue_ids(&pdb, ResidueOrder::Numeric);
+///
+/// for (chain_id, ids) in residue_ids {
+///     let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+///     println!("Chain {}: {}", chain_id, ids.join(", "));
+/// }
+/// ```
+pub fn identify_residue_ids(
+    structure: &pdbtbx::PDB,
+    order: ResidueOrder,
+) -> HashMap<String, Vec<ResidueId>> {
+    let mut residue_ids: HashMap<String, Vec<ResidueId>> = chain_residues(structure)
+        .into_iter()
+        .map(|(chain, residues)| {
+            let ids = residues.into_iter().map(ResidueId::from_residue).collect();
+            (chain.to_string(), ids)
+        })
+        .collect();
+
+    if order == ResidueOrder::Numeric {
+        for ids in residue_ids.values_mut() {
+            ids.sort();
+        }
+    }
+
+    residue_ids
+}
+
+/// Returns the residues of each chain, with the chains and residues in file order. Only the first
+/// residue with a given ID is kept, so residues repeated in later models are skipped.
+pub(crate) fn chain_residues(structure: &pdbtbx::PDB) -> Vec<(&str, Vec<&pdbtbx::Residue>)> {
+    let mut chains: Vec<(&str, Vec<&pdbtbx::Residue>)> = Vec::new();
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    let mut seen: HashSet<(&str, ResidueId)> = HashSet::new();
+
+    for chain in structure.chains() {
+        let i = *index.entry(chain.id()).or_insert_with(|| {
+            chains.push((chain.id(), Vec::new()));
+            chains.len() - 1
+        });
+        for residue in chain.residues() {
+            if seen.insert((chain.id(), ResidueId::from_residue(residue))) {
+                chains[i].1.push(residue);
+            }
+        }
+    }
+
+    chains
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use pdbtbx::ReadOptions;
+
+    #[test]
+    fn test_residue_id() {
+        let mut ids: Vec<ResidueId> = ["10", "52B", "9", "52", "-1", "52A"]
+            .iter()
+            .map(|id| id.parse().unwrap())
+            .collect();
+        ids.sort();
+
+        let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+        assert_eq!(ids, vec!["-1", "9", "10", "52", "52A", "52B"]);
+
+        assert!("A".parse::<ResidueId>().is_err());
+        assert!("52AB".parse::<ResidueId>().is_err());
+        assert!(matches!(
+            "".parse::<ResidueId>(),
+            Err(PdbHandlerError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_identify_residue_ids() {
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/residue_ids.pdb")
+            .unwrap();
+
+        let file_order = identify_residue_ids(&structure, ResidueOrder::File);
+        assert_eq!(
+            file_order["A"],
+            vec![
+                ResidueId::new(10, None),
+                ResidueId::new(52, Some('B')),
+                ResidueId::new(52, Some('A')),
+                ResidueId::new(9, None),
+            ]
+        );
+
+        let numeric_order = identify_residue_ids(&structure, ResidueOrder::Numeric);
+        assert_eq!(
+            numeric_order["A"],
+            vec![
+                ResidueId::new(9, None),
+                ResidueId::new(10, None),
+                ResidueId::new(52, Some('A')),
+                ResidueId::new(52, Some('B')),
+            ]
+        );
+        assert_eq!(numeric_order["B"], vec![ResidueId::new(1, None)]);
+    }
+}
@@ -0,0 +1,289 @@
+use crate::{classify_residue_name, MolecularType, ResidueId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The maximum length, in Ångström, of the C–N peptide bond and of the O3'–P phosphodiester bond
+/// between two consecutive residues. Longer bonds are reported as chain breaks.
+pub const MAX_BOND_LENGTH: f64 = 2.0;
+
+/// The kind of a [`ResidueGap`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum GapKind {
+    /// The residues are not bonded: the C–N or O3'–P distance is over [`MAX_BOND_LENGTH`], or
+    /// the bond atoms are missing and the residue numbering jumps.
+    ChainBreak,
+    /// The residue numbering jumps, but the residues are bonded.
+    NumberingJump,
+}
+
+/// A discontinuity between two consecutive residues of a chain.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResidueGap {
+    /// The residue before the gap.
+    pub previous: ResidueId,
+    /// The residue after the gap.
+    pub next: ResidueId,
+    /// Whether the gap is a chain break or only a jump in the residue numbering.
+    pub kind: GapKind,
+    /// The C–N or O3'–P distance between the residues, or `None` if any of the atoms is missing.
+    pub distance: Option<f64>,
+}
+
+/// A run of bonded residues, given by its first and last residue.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Segment {
+    /// The first residue of the segment.
+    pub start: ResidueId,
+    /// The last residue of the segment, which is the same as `start` for a single residue.
+    pub end: ResidueId,
+}
+
+/// The segments and gaps of a single chain, as found by [`identify_chain_segments`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ChainSegments {
+    /// The segments of the chain, in file order. Segments are separated by chain breaks only.
+    pub segments: Vec<Segment>,
+    /// Every chain break and numbering jump of the chain, in file order.
+    pub gaps: Vec<ResidueGap>,
+}
+
+impl ChainSegments {
+    /// Returns the gaps that are real chain breaks.
+    pub fn breaks(&self) -> impl Iterator<Item = &ResidueGap> {
+        self.gaps
+            .iter()
+            .filter(|gap| gap.kind == GapKind::ChainBreak)
+    }
+
+    /// Returns true if the chain has no breaks.
+    pub fn is_continuous(&self) -> bool {
+        self.breaks().next().is_none()
+    }
+}
+
+/// Identifies the chain breaks and numbering gaps in each chain of the given PDB structure.
+///
+/// Consecutive polymer residues are checked for their backbone bond: the C–N peptide bond for
+/// proteins and the O3'–P bond for nucleic acids. A bond longer than [`MAX_BOND_LENGTH`] is a
+/// chain break, e.g. a missing loop. A jump in the residue numbering between bonded residues is
+/// flagged as a [`GapKind::NumberingJump`], which is not a break. When the bond atoms are missing,
+/// a numbering jump is taken as a chain break. Residue numbers are consecutive when the serial
+/// number increases by one, or stays the same with a later insertion code, e.g. `52`, `52A`, `53`.
+///
+/// # Arguments
+///
+/// * `structure` - A reference to a `pdbtbx::PDB` structure representing the PDB file to be analyzed.
+///
+/// # Returns
+///
+/// A `HashMap<String, ChainSegments>` where each key is the ID of a chain with polymer residues
+/// and each value holds the segments and gaps of that chain.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::identify_chain_segments;
+///
+/// let (mut pdb, _errors) = pdbtbx::open("example-pdbs/1crn.pdb").unwrap();
+/// let chain_segments = identify_chain_segments(&pdb);
+///
+/// for (chain_id, chain) in chain_segments {
+///     for segment in &chain.segments {
+///         println!("Chain {}: {}-{}", chain_id, segment.start, segment.end);
+///     }
+/// }
+/// ```
+///
+/// # Notes
+///
+/// Only polymer residues of the first model are considered: residues with a protein, DNA or RNA
+/// name, including modified residues, and unknown residues with backbone atoms (N, CA and C, or P
+/// and O3'). Waters, ions and ligands are skipped.
+pub fn identify_chain_segments(structure: &pdbtbx::PDB) -> HashMap<String, ChainSegments> {
+    let mut chain_segments: HashMap<String, ChainSegments> = HashMap::new();
+    let Some(model) = structure.models().next() else {
+        return chain_segments;
+    };
+
+    for chain in model.chains() {
+        let residues: Vec<(&pdbtbx::Residue, MolecularType)> = chain
+            .residues()
+            .filter_map(|residue| Some((residue, polymer_type(residue)?)))
+            .collect();
+        let Some((first, _)) = residues.first() else {
+            continue;
+        };
+
+        let segments = chain_segments.entry(chain.id().to_string()).or_default();
+        let mut segment = Segment {
+            start: ResidueId::from_residue(first),
+            end: ResidueId::from_residue(first),
+        };
+
+        for pair in residues.windows(2) {
+            let (previous, previous_type) = &pair[0];
+            let (next, _) = &pair[1];
+            let previous_id = ResidueId::from_residue(previous);
+            let next_id = ResidueId::from_residue(next);
+
+            let (from, to) = match previous_type {
+                MolecularType::Protein => ("C", "N"),
+                _ => ("O3'", "P"),
+            };
+            let distance = atom_distance(previous, from, next, to);
+            let consecutive = is_consecutive(previous_id, next_id);
+            let kind = match distance {
+                Some(distance) if distance > MAX_BOND_LENGTH => Some(GapKind::ChainBreak),
+                None if !consecutive => Some(GapKind::ChainBreak),
+                _ if !consecutive => Some(GapKind::NumberingJump),
+                _ => None,
+            };
+
+            if let Some(kind) = kind {
+                segments.gaps.push(ResidueGap {
+                    previous: previous_id,
+                    next: next_id,
+                    kind,
+                    distance,
+                });
+                if kind == GapKind::ChainBreak {
+                    segments.segments.push(segment);
+                    segment.start = next_id;
+                }
+            }
+            segment.end = next_id;
+        }
+        segments.segments.push(segment);
+    }
+
+    chain_segments
+}
+
+/// Returns the molecular type of a polymer residue: a residue with a protein, DNA or RNA name, or
+/// an unknown residue with the N, CA and C atoms of an amino acid or the P and O3' atoms of a
+/// nucleotide. Returns `None` for other residues.
+fn polymer_type(residue: &pdbtbx::Residue) -> Option<MolecularType> {
+    let mol_type = residue.name().and_then(classify_residue_name);
+    if let Some(mol_type @ (MolecularType::Protein | MolecularType::Dna | MolecularType::Rna)) =
+        mol_type
+    {
+        return Some(mol_type);
+    }
+
+    let has_atoms = |names: &[&str]| {
+        names
+            .iter()
+            .all(|name| residue.atoms().any(|atom| atom.name() == *name))
+    };
+    if has_atoms(&["N", "CA", "C"]) {
+        Some(MolecularType::Protein)
+    } else if has_atoms(&["P", "O3'"]) {
+        Some(MolecularType::Dna)
+    } else {
+        None
+    }
+}
+
+/// Returns the distance between atom `from` of residue `a` and atom `to` of residue `b`.
+fn atom_distance(a: &pdbtbx::Residue, from: &str, b: &pdbtbx::Residue, to: &str) -> Option<f64> {
+    let from = a.atoms().find(|atom| atom.name() == from)?;
+    let to = b.atoms().find(|atom| atom.name() == to)?;
+    Some(from.distance(to))
+}
+
+/// Returns true if `next` directly follows `previous` in the residue numbering.
+fn is_consecutive(previous: ResidueId, next: ResidueId) -> bool {
+    previous.serial.checked_add(1) == Some(next.serial)
+        || (next.serial == previous.serial && next > previous)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use pdbtbx::ReadOptions;
+
+    #[test]
+    fn test_identify_chain_segments_1crn() {
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("example-pdbs/1crn.pdb")
+            .unwrap();
+
+        let chain_segments = identify_chain_segments(&structure);
+
+        assert_eq!(chain_segments.len(), 1);
+        let chain = &chain_segments["A"];
+        assert!(chain.is_continuous());
+        assert!(chain.gaps.is_empty());
+        assert_eq!(
+            chain.segments,
+            vec![Segment {
+                start: ResidueId::new(1, None),
+                end: ResidueId::new(46, None),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_identify_chain_segments() {
+        // Chain A: 1-2 bonded, 2-10 bonded with a numbering jump, 10-10A bonded, 10A-20 broken.
+        // Chain B: DNA with a broken O3'-P bond between 2 and 3, then a water.
+        // Chain C: an unknown residue with a protein backbone between ALA 1 and GLY 3.
+        let (structure, _) = ReadOptions::default()
+            .set_format(pdbtbx::Format::Pdb)
+            .read("test_data/segments.pdb")
+            .unwrap();
+
+        let chain_segments = identify_chain_segments(&structure);
+
+        let chain_a = &chain_segments["A"];
+        assert_eq!(chain_a.gaps.len(), 2);
+        assert_eq!(chain_a.gaps[0].kind, GapKind::NumberingJump);
+        assert_eq!(chain_a.gaps[0].previous, ResidueId::new(2, None));
+        assert_eq!(chain_a.gaps[0].next, ResidueId::new(10, None));
+        assert_eq!(chain_a.gaps[1].kind, GapKind::ChainBreak);
+        assert_eq!(chain_a.gaps[1].previous, ResidueId::new(10, Some('A')));
+        assert!((chain_a.gaps[1].distance.unwrap() - 10.0).abs() < 1e-6);
+        assert!(!chain_a.is_continuous());
+        assert_eq!(
+            chain_a.segments,
+            vec![
+                Segment {
+                    start: ResidueId::new(1, None),
+                    end: ResidueId::new(10, Some('A')),
+                },
+                Segment {
+                    start: ResidueId::new(20, None),
+                    end: ResidueId::new(20, None),
+                },
+            ]
+        );
+
+        let chain_b = &chain_segments["B"];
+        assert_eq!(chain_b.breaks().count(), 1);
+        assert_eq!(
+            chain_b.segments,
+            vec![
+                Segment {
+                    start: ResidueId::new(1, None),
+                    end: ResidueId::new(2, None),
+                },
+                Segment {
+                    start: ResidueId::new(3, None),
+                    end: ResidueId::new(3, None),
+                },
+            ]
+        );
+
+        let chain_c = &chain_segments["C"];
+        assert!(chain_c.gaps.is_empty());
+        assert_eq!(
+            chain_c.segments,
+            vec![Segment {
+                start: ResidueId::new(1, None),
+                end: ResidueId::new(3, None),
+            }]
+        );
+    }
+}
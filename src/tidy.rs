@@ -0,0 +1,376 @@
+use crate::elements::fill_missing_element;
+use crate::{classify_residue_name, MolecularType, PdbHandlerError};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+
+/// The right-justified numeric fields of `ATOM` and `HETATM` records, with their column ranges.
+const NUMERIC_FIELDS: [(&str, usize, usize); 7] = [
+    ("serial", 6, 11),
+    ("resSeq", 22, 26),
+    ("x", 30, 38),
+    ("y", 38, 46),
+    ("z", 46, 54),
+    ("occupancy", 54, 60),
+    ("tempFactor", 60, 66),
+];
+
+/// A fix applied by [`tidy`].
+///
+/// Line numbers start at 1 and refer to the input. Records that were added refer to the last
+/// input line before them.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TidyFix {
+    /// A CRLF line ending was replaced by LF.
+    LineEnding { line: usize },
+    /// Tabs were expanded to spaces.
+    Tabs { line: usize },
+    /// A numeric field was right-justified in its columns, e.g. `serial` or `x`.
+    Justified { line: usize, field: String },
+    /// A missing element symbol was inferred from the atom name.
+    Element { line: usize, element: String },
+    /// A `TER` record was added after the last residue of a chain.
+    Ter { line: usize, chain: String },
+    /// The serial number was shifted to follow the `TER` records added before it.
+    Serial { line: usize },
+    /// An `END` record was added at the end of the file.
+    End { line: usize },
+}
+
+/// Normalises a PDB text stream and writes the result to `writer`.
+///
+/// Legacy and hand-edited PDB files often deviate from the wwPDB format in ways that confuse
+/// downstream tools. This function applies the following fixes, one line at a time:
+///
+/// * CRLF line endings are replaced by LF and tabs are expanded to spaces (tab stops every 8 columns).
+/// * Numeric fields of `ATOM` and `HETATM` records (serial, residue number, coordinates, occupancy
+///   and temperature factor) are right-justified in their columns.
+/// * Missing element symbols (columns 77-78) are inferred from the atom name.
+/// * A `TER` record is added after the last polymer residue of each chain, if missing. Like the
+///   `TER` records of the wwPDB, it takes the serial number after the last atom of the chain, so
+///   the serial numbers of the `ATOM`, `HETATM`, `ANISOU` and `TER` records after it are shifted
+///   by the number of `TER` records added so far.
+/// * An `END` record is added at the end of the file, if missing.
+///
+/// # Arguments
+///
+/// * `reader` - A `BufRead` over the PDB content.
+/// * `writer` - The destination of the normalised content.
+///
+/// # Returns
+///
+/// The list of fixes that were applied, in order, or an error if the content cannot be read, is
+/// not valid UTF-8 or cannot be written.
+///
+/// # Example
+///
+/// ```rust
+/// use pdb_handler::{tidy, TidyFix};
+///
+/// let content = "ATOM      1  N   GLY A   1      17.047  14.099   3.625  1.00 13.79\r\n";
+/// let mut output = Vec::new();
+/// let fixes = tidy(content.as_bytes(), &mut output).unwrap();
+///
+/// assert_eq!(fixes[0], TidyFix::LineEnding { line: 1 });
+/// assert!(fixes.contains(&TidyFix::End { line: 1 }));
+/// ```
+///
+/// # Notes
+///
+/// Fields are expected in their standard columns; misaligned records are not realigned. Serial
+/// numbers that would not fit in their five columns after the shift are left unchanged.
+pub fn tidy<R: BufRead, W: Write>(
+    mut reader: R,
+    mut writer: W,
+) -> Result<Vec<TidyFix>, PdbHandlerError> {
+    let mut fixes = Vec::new();
+    let mut line_number = 0;
+    // The last polymer record that was not yet followed by a `TER` record
+    let mut open_chain: Option<String> = None;
+    let mut last_record = String::new();
+    // The number of `TER` records added so far
+    let mut added_ters = 0;
+    let mut buffer = Vec::new();
+
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            break;
+        }
+        line_number += 1;
+
+        let mut line = String::from_utf8(std::mem::take(&mut buffer))
+            .map_err(|_| PdbHandlerError::Encoding { line: line_number })?;
+        if line.ends_with('\n') {
+            line.pop();
+        }
+        if line.ends_with('\r') {
+            line.pop();
+            fixes.push(TidyFix::LineEnding { line: line_number });
+        }
+        if line.contains('\t') {
+            line = expand_tabs(&line);
+            fixes.push(TidyFix::Tabs { line: line_number });
+        }
+
+        let record = line.get(..6).unwrap_or(&line).trim_end().to_string();
+        let is_coordinate = record == "ATOM" || record == "HETATM";
+        let is_polymer = is_coordinate && is_polymer_record(&line);
+
+        // Close the previous chain before anything that cannot continue it
+        if let Some(last) = &open_chain {
+            let continues_chain = is_polymer && line.get(21..22) == last.get(21..22);
+            let is_trailing = record == "ANISOU" || record == "SIGATM" || record == "SIGUIJ";
+            if record == "TER" {
+                open_chain = None;
+            } else if !continues_chain && !is_trailing && !record.is_empty() {
+                add_ter(last, line_number - 1, &mut writer, &mut fixes)?;
+                added_ters += 1;
+                open_chain = None;
+            }
+        }
+
+        if is_coordinate {
+            justify_numbers(&mut line, line_number, &mut fixes);
+        }
+        let is_numbered = is_coordinate || record == "ANISOU" || record == "TER";
+        if is_numbered && added_ters > 0 && shift_serial(&mut line, added_ters) {
+            fixes.push(TidyFix::Serial { line: line_number });
+        }
+        if is_coordinate {
+            if let Some(element) = fill_missing_element(&mut line) {
+                fixes.push(TidyFix::Element {
+                    line: line_number,
+                    element,
+                });
+            }
+        }
+        if is_polymer {
+            open_chain = Some(line.clone());
+        }
+
+        if !record.is_empty() {
+            last_record = record;
+        }
+        writeln!(writer, "{}", line)?;
+    }
+
+    if let Some(ter) = &open_chain {
+        add_ter(ter, line_number, &mut writer, &mut fixes)?;
+        last_record = "TER".to_string();
+    }
+    if last_record != "END" {
+        writeln!(writer, "{:<80}", "END")?;
+        fixes.push(TidyFix::End { line: line_number });
+    }
+
+    Ok(fixes)
+}
+
+/// Expands tabs to spaces, with tab stops every 8 columns.
+fn expand_tabs(line: &str) -> String {
+    let mut expanded = String::with_capacity(line.len());
+    for c in line.chars() {
+        if c == '\t' {
+            let width = 8 - expanded.chars().count() % 8;
+            expanded.push_str(&" ".repeat(width));
+        } else {
+            expanded.push(c);
+        }
+    }
+    expanded
+}
+
+/// Right-justifies the numeric fields of an `ATOM` or `HETATM` record.
+fn justify_numbers(line: &mut String, line_number: usize, fixes: &mut Vec<TidyFix>) {
+    if !line.is_ascii() {
+        return;
+    }
+
+    for (field, start, end) in NUMERIC_FIELDS {
+        let Some(value) = line.get(start..end) else {
+            continue;
+        };
+        let trimmed = value.trim();
+        if trimmed.is_empty() || trimmed.contains(' ') || !value.ends_with(' ') {
+            continue;
+        }
+
+        let justified = format!("{:>width$}", trimmed, width = end - start);
+        line.replace_range(start..end, &justified);
+        fixes.push(TidyFix::Justified {
+            line: line_number,
+            field: field.to_string(),
+        });
+    }
+}
+
+/// Adds `shift` to the serial number in columns 7-11, and returns true if it was changed.
+fn shift_serial(line: &mut String, shift: usize) -> bool {
+    let Some(serial) = line
+        .get(6..11)
+        .and_then(|serial| serial.trim().parse::<usize>().ok())
+    else {
+        return false;
+    };
+    let shifted = format!("{:>5}", serial + shift);
+    if shifted.len() > 5 {
+        return false;
+    }
+    line.replace_range(6..11, &shifted);
+    true
+}
+
+/// Returns true if the record belongs to a polymer residue, including modified residues given
+/// as `HETATM` records.
+fn is_polymer_record(line: &str) -> bool {
+    if line.starts_with("ATOM") {
+        return true;
+    }
+    let res_name = line.get(17..20).unwrap_or("").trim();
+    matches!(
+        classify_residue_name(res_name),
+        Some(MolecularType::Protein | MolecularType::Dna | MolecularType::Rna)
+    )
+}
+
+/// Writes a `TER` record for the residue of the given coordinate record.
+fn add_ter<W: Write>(
+    last: &str,
+    line_number: usize,
+    writer: &mut W,
+    fixes: &mut Vec<TidyFix>,
+) -> Result<(), PdbHandlerError> {
+    let field = |start: usize, end: usize| last.get(start..end).unwrap_or("");
+    let chain = field(21, 22).to_string();
+    // The serial number follows the one of the last atom of the chain
+    let serial = field(6, 11)
+        .trim()
+        .parse::<usize>()
+        .map(|serial| (serial + 1).to_string())
+        .unwrap_or_default();
+    let ter = format!(
+        "TER   {:>5}      {:>3} {}{:>4}{}",
+        serial,
+        field(17, 20).trim(),
+        chain,
+        field(22, 26).trim(),
+        field(26, 27),
+    );
+    writeln!(writer, "{:<80}", ter)?;
+
+    fixes.push(TidyFix::Ter {
+        line: line_number,
+        chain,
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_tidy() {
+        let content = std::fs::read("test_data/pdb_legacy.pdb").unwrap();
+        let mut output = Vec::new();
+        let fixes = tidy(content.as_slice(), &mut output).unwrap();
+
+        assert_eq!(
+            fixes,
+            vec![
+                TidyFix::LineEnding { line: 1 },
+                TidyFix::Tabs { line: 1 },
+                TidyFix::LineEnding { line: 2 },
+                TidyFix::Element {
+                    line: 2,
+                    element: "N".to_string()
+                },
+                TidyFix::Justified {
+                    line: 3,
+                    field: "serial".to_string()
+                },
+                TidyFix::Justified {
+                    line: 3,
+                    field: "x".to_string()
+                },
+                TidyFix::Element {
+                    line: 3,
+                    element: "C".to_string()
+                },
+                TidyFix::Ter {
+                    line: 3,
+                    chain: "A".to_string()
+                },
+                TidyFix::Serial { line: 4 },
+                TidyFix::Ter {
+                    line: 4,
+                    chain: "B".to_string()
+                },
+                TidyFix::Serial { line: 5 },
+                TidyFix::Element {
+                    line: 5,
+                    element: "ZN".to_string()
+                },
+                TidyFix::End { line: 5 },
+            ]
+        );
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[0], "REMARK  1 HAND EDITED");
+        assert_eq!(
+            lines[2],
+            "ATOM      2  CA  ALA A   1      16.967  12.784   4.338  1.00 10.80           C  "
+        );
+        assert_eq!(&lines[3][..11], "TER       3");
+        assert_eq!(&lines[3][17..20], "ALA");
+        assert_eq!(&lines[3][21..22], "A");
+        assert_eq!(&lines[3][22..26], "   1");
+        assert_eq!(&lines[4][..11], "ATOM      4");
+        assert_eq!(&lines[5][..11], "TER       5");
+        assert_eq!(&lines[5][17..20], "GLY");
+        assert_eq!(&lines[5][21..22], "B");
+        assert_eq!(&lines[5][22..26], "   1");
+        assert_eq!(&lines[6][..11], "HETATM    6");
+        assert_eq!(&lines[6][76..78], "ZN");
+        assert_eq!(lines[7].trim_end(), "END");
+        assert!(!output.contains('\r'));
+
+        // The output is already tidy
+        let mut again = Vec::new();
+        assert!(tidy(output.as_bytes(), &mut again).unwrap().is_empty());
+        assert_eq!(String::from_utf8(again).unwrap(), output);
+    }
+
+    #[test]
+    fn test_tidy_modified_residue_and_water() {
+        let content = "\
+ATOM      1  CA  ALA A   1      16.967  12.784   4.338  1.00 10.80           C
+HETATM    2  CA  MSE A   2      16.967  12.784   4.338  1.00 10.80           C
+HETATM    3  O   HOH A 101      16.967  12.784   4.338  1.00 10.80           O
+END
+";
+        let mut output = Vec::new();
+        let fixes = tidy(content.as_bytes(), &mut output).unwrap();
+
+        assert_eq!(
+            fixes,
+            vec![
+                TidyFix::Ter {
+                    line: 2,
+                    chain: "A".to_string()
+                },
+                TidyFix::Serial { line: 3 },
+            ]
+        );
+        let output = String::from_utf8(output).unwrap();
+        let ter = output.lines().nth(2).unwrap();
+        assert_eq!(&ter[..11], "TER       3");
+        assert_eq!(&ter[17..20], "MSE");
+        assert_eq!(&ter[21..22], "A");
+        assert_eq!(&ter[22..26], "   2");
+        assert_eq!(&output.lines().nth(3).unwrap()[..11], "HETATM    4");
+    }
+}
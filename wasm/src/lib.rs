@@ -1,6 +1,5 @@
 use pdbtbx::ReadOptions;
 use serde::{Deserialize, Serialize};
-use std::io::{BufReader, Cursor};
 use tsify::Tsify;
 use wasm_bindgen::prelude::*;
 
@@ -62,6 +61,21 @@ impl PdbHandlerApi {
             .collect()
     }
 
+    pub fn list_residue_ranges(&self) -> Vec<ChainData> {
+        let ranges = pdb_handler::ResidueRanges::from_structure(&self.structure);
+        ranges
+            .chains()
+            .map(|chain| ChainData {
+                chain: chain.to_string(),
+                items: ranges
+                    .ranges(chain)
+                    .iter()
+                    .map(|range| range.to_string())
+                    .collect(),
+            })
+            .collect()
+    }
+
     pub fn guess_moltype(&self) -> Vec<ChainData> {
         pdb_handler::identify_molecular_types(&self.structure)
             .into_iter()
@@ -84,16 +98,13 @@ impl PdbHandlerApi {
 }
 
 pub fn load_pdb_from_bytes(bytes: &js_sys::Uint8Array) -> Result<pdbtbx::PDB, PDBErrorWrapper> {
-    let pdb_string = if bytes.is_null() {
-        String::new()
+    let bytes = if bytes.is_null() {
+        Vec::new()
     } else {
-        let vec = bytes.to_vec();
-        String::from_utf8(vec).unwrap()
+        bytes.to_vec()
     };
 
-    let bytes = pdb_string.as_bytes().to_vec();
-    let cursor = Cursor::new(bytes);
-    let buf = BufReader::new(cursor);
+    let buf = pdb_handler::pad_lines_from_reader(bytes.as_slice());
 
     let mut opts = ReadOptions::new();
     opts.set_format(pdbtbx::Format::Pdb)